@@ -1,5 +1,9 @@
+use chrono::NaiveDate;
 use clap::Parser;
 
+use crate::repository::{GroupBy, MergeHandling};
+use crate::table::Column;
+
 /// GitHistoryExplorer: Analyze and display commit history information from a Git repository
 #[derive(Parser, Debug)]
 #[clap(
@@ -8,7 +12,265 @@ use clap::Parser;
     about = "Explore commit history in a Git repository"
 )]
 pub struct Args {
-    /// Path to the Git repository (default: current directory)
-    #[clap(short, long, default_value = ".")]
-    pub path: String,
+    /// Read options from a TOML file; flags given on the command line take precedence
+    #[clap(long, value_name = "FILE")]
+    pub config: Option<String>,
+
+    /// Path to the Git repository (default: discovered from GIT_DIR or the current directory)
+    #[clap(short, long)]
+    pub path: Option<String>,
+
+    /// Fix the total table width in columns, truncating long emails to fit
+    #[clap(short, long)]
+    pub width: Option<usize>,
+
+    /// Report the most frequent words used in commit messages, and message lengths per author
+    #[clap(long, overrides_with = "no_analyze_messages")]
+    pub analyze_messages: bool,
+
+    /// Number of words to list when analyzing commit messages (default: 10)
+    #[clap(long)]
+    pub top_words: Option<usize>,
+
+    /// Only count commits in `<from>..<to>`, like `git log from..to`
+    #[clap(long)]
+    pub range: Option<String>,
+
+    /// Walk history from this revision instead of HEAD (cannot be combined with --range)
+    #[clap(long, value_name = "REV")]
+    pub head: Option<String>,
+
+    /// Only count commits made on or after this date (YYYY-MM-DD)
+    #[clap(long)]
+    pub since: Option<NaiveDate>,
+
+    /// Only count commits made on or before this date (YYYY-MM-DD)
+    #[clap(long)]
+    pub until: Option<NaiveDate>,
+
+    /// Ignore commits made on a Saturday or Sunday (by their UTC date)
+    #[clap(long, overrides_with = "no_exclude_weekends")]
+    pub exclude_weekends: bool,
+
+    /// Only count commits made within this many days of the earliest commit
+    #[clap(long, value_name = "DAYS")]
+    pub first_n_days: Option<u32>,
+
+    /// Stop after counting this many commits that pass the filters
+    #[clap(long)]
+    pub max_commits: Option<usize>,
+
+    /// Stop after walking this many commits, whether or not they pass the filters
+    #[clap(long)]
+    pub max_scan: Option<usize>,
+
+    /// List how many commits were made from each UTC offset
+    #[clap(long, overrides_with = "no_timezones")]
+    pub timezones: bool,
+
+    /// List how many commits changed files of each extension (slower, diffs every commit)
+    #[clap(long, overrides_with = "no_by_extension")]
+    pub by_extension: bool,
+
+    /// Write a CSV with a row per date, a column per author and commit counts in the cells
+    #[clap(long, value_name = "PATH")]
+    pub export_matrix: Option<String>,
+
+    /// Only count commits that change the given file or directory, like `git log -- <PATH>`
+    #[clap(long, value_name = "PATH")]
+    pub follow: Option<String>,
+
+    /// Ignore root commits (those without parents), such as an initial import
+    #[clap(long, overrides_with = "no_skip_root")]
+    pub skip_root: bool,
+
+    /// Ignore revert commits, detected by a `Revert "..."` subject or `This reverts commit` line
+    #[clap(long, overrides_with = "no_ignore_reverts")]
+    pub ignore_reverts: bool,
+
+    /// Count merge commits normally, skip them, or credit them all to a `(merges)` row (default: count)
+    #[clap(long, value_enum)]
+    pub merge_handling: Option<MergeHandling>,
+
+    /// Also count local commits from the HEAD reflog that no ref reaches any more, e.g. amended ones
+    #[clap(long, overrides_with = "no_include_reflog")]
+    pub include_reflog: bool,
+
+    /// Reduce GitHub no-reply emails to the bare username so their variants merge
+    #[clap(long, overrides_with = "no_normalize_github_noreply")]
+    pub normalize_github_noreply: bool,
+
+    /// Treat each distinct name under an email as a separate author, e.g. for shared bot accounts
+    #[clap(long, overrides_with = "no_split_by_name")]
+    pub split_by_name: bool,
+
+    /// Aggregate commits per author email or per email domain (default: email)
+    #[clap(long, value_enum)]
+    pub group_by: Option<GroupBy>,
+
+    /// Flag authors with `!` when their last commit is more than this many days ago
+    #[clap(long, value_name = "DAYS")]
+    pub inactive_days: Option<i64>,
+
+    /// Mark authors listed in this file (one email per line) with `+`
+    #[clap(long, value_name = "FILE")]
+    pub team: Option<String>,
+
+    /// Mark your own row with `*`, matched against the repository's configured user.email
+    #[clap(long, overrides_with = "no_mark_me")]
+    pub mark_me: bool,
+
+    /// Draw a bar next to each commit count, scaled to the most active author
+    #[clap(long, overrides_with = "no_bars")]
+    pub bars: bool,
+
+    /// Format numbers and dates for a locale such as `de`, `en-GB` or `fr_FR`
+    #[clap(long, value_name = "TAG")]
+    pub locale: Option<String>,
+
+    /// Comma-separated columns to show first, in this order, e.g. `commits,email`
+    #[clap(long, value_enum, value_delimiter = ',', value_name = "LIST")]
+    pub columns: Vec<Column>,
+
+    /// Abbreviate large commit counts, e.g. `12.3k` or `1.1M`
+    #[clap(long, overrides_with = "no_compact")]
+    pub compact: bool,
+
+    /// Add a column with a sparkline of each author's commits over the last twelve months
+    #[clap(long, overrides_with = "no_sparkline")]
+    pub sparkline: bool,
+
+    /// Add a column with the number of distinct files each author changed (slower, diffs every commit)
+    #[clap(long, overrides_with = "no_files_touched")]
+    pub files_touched: bool,
+
+    /// Add a column with the total number of files each author's commits changed (slower, compares every commit's tree)
+    #[clap(long, overrides_with = "no_files_changed")]
+    pub files_changed: bool,
+
+    /// Don't check the working tree for uncommitted changes (faster on large checkouts)
+    #[clap(long, overrides_with = "no_skip_status")]
+    pub skip_status: bool,
+
+    /// How many more times to try when the repository is locked by another git process (default: 3)
+    #[clap(long)]
+    pub retries: Option<u32>,
+
+    /// Add a column with the number of distinct names each author committed under
+    #[clap(long, overrides_with = "no_aliases")]
+    pub aliases: bool,
+
+    /// Add a column with each author's longest stretch of days between commits
+    #[clap(long, overrides_with = "no_longest_gap")]
+    pub longest_gap: bool,
+
+    /// Add a column with each author's average commits per week between their first and last commit
+    #[clap(long, overrides_with = "no_weekly_rate")]
+    pub weekly_rate: bool,
+
+    /// Add a column with an arrow showing whether each author committed more (↑), less (↓) or as much (→) in the second half of the period
+    #[clap(long, overrides_with = "no_trend")]
+    pub trend: bool,
+
+    /// Add a column from 0 to 1 showing how evenly each author's commits are spread over the days they were active
+    #[clap(long, overrides_with = "no_spread")]
+    pub spread: bool,
+
+    /// Add a column with the lines each author added or deleted in files with this extension, e.g. `rs`
+    #[clap(long, value_name = "EXT")]
+    pub weight_ext: Option<String>,
+
+    /// Add a column with the number of annotated tags (e.g. releases) each author created
+    #[clap(long, overrides_with = "no_tags")]
+    pub tags: bool,
+
+    /// Warn when last month's commits fall below FACTOR times the average of the six months before
+    #[clap(long, value_name = "FACTOR")]
+    pub alert_low: Option<f64>,
+
+    /// Warn when last month's commits rise above FACTOR times the average of the six months before
+    #[clap(long, value_name = "FACTOR")]
+    pub alert_high: Option<f64>,
+
+    /// Also log debug diagnostics on stderr, such as how long collecting and reporting took
+    #[clap(long, overrides_with = "no_verbose")]
+    pub verbose: bool,
+
+    /// Don't log notes or warnings on stderr, only errors; for scripted runs
+    #[clap(long, conflicts_with = "verbose", overrides_with = "no_quiet")]
+    pub quiet: bool,
+
+    // `--no-<flag>` forms of the switches above, so the command line can turn off what a
+    // config file turns on
+    #[clap(long, hide = true, overrides_with = "analyze_messages")]
+    pub no_analyze_messages: bool,
+
+    #[clap(long, hide = true, overrides_with = "exclude_weekends")]
+    pub no_exclude_weekends: bool,
+
+    #[clap(long, hide = true, overrides_with = "timezones")]
+    pub no_timezones: bool,
+
+    #[clap(long, hide = true, overrides_with = "by_extension")]
+    pub no_by_extension: bool,
+
+    #[clap(long, hide = true, overrides_with = "skip_root")]
+    pub no_skip_root: bool,
+
+    #[clap(long, hide = true, overrides_with = "ignore_reverts")]
+    pub no_ignore_reverts: bool,
+
+    #[clap(long, hide = true, overrides_with = "include_reflog")]
+    pub no_include_reflog: bool,
+
+    #[clap(long, hide = true, overrides_with = "normalize_github_noreply")]
+    pub no_normalize_github_noreply: bool,
+
+    #[clap(long, hide = true, overrides_with = "split_by_name")]
+    pub no_split_by_name: bool,
+
+    #[clap(long, hide = true, overrides_with = "mark_me")]
+    pub no_mark_me: bool,
+
+    #[clap(long, hide = true, overrides_with = "bars")]
+    pub no_bars: bool,
+
+    #[clap(long, hide = true, overrides_with = "compact")]
+    pub no_compact: bool,
+
+    #[clap(long, hide = true, overrides_with = "sparkline")]
+    pub no_sparkline: bool,
+
+    #[clap(long, hide = true, overrides_with = "files_touched")]
+    pub no_files_touched: bool,
+
+    #[clap(long, hide = true, overrides_with = "files_changed")]
+    pub no_files_changed: bool,
+
+    #[clap(long, hide = true, overrides_with = "skip_status")]
+    pub no_skip_status: bool,
+
+    #[clap(long, hide = true, overrides_with = "aliases")]
+    pub no_aliases: bool,
+
+    #[clap(long, hide = true, overrides_with = "tags")]
+    pub no_tags: bool,
+
+    #[clap(long, hide = true, overrides_with = "longest_gap")]
+    pub no_longest_gap: bool,
+
+    #[clap(long, hide = true, overrides_with = "weekly_rate")]
+    pub no_weekly_rate: bool,
+
+    #[clap(long, hide = true, overrides_with = "trend")]
+    pub no_trend: bool,
+
+    #[clap(long, hide = true, overrides_with = "spread")]
+    pub no_spread: bool,
+
+    #[clap(long, hide = true, overrides_with = "verbose")]
+    pub no_verbose: bool,
+
+    #[clap(long, hide = true, overrides_with = "quiet")]
+    pub no_quiet: bool,
 }
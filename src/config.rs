@@ -0,0 +1,227 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::fs;
+
+use crate::cli::Args;
+use crate::repository::{GroupBy, MergeHandling};
+use crate::table::Column;
+
+/// Options read from a `--config` TOML file, named like their command-line flags
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct FileConfig {
+    path: Option<String>,
+    width: Option<usize>,
+    analyze_messages: Option<bool>,
+    top_words: Option<usize>,
+    range: Option<String>,
+    head: Option<String>,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+    exclude_weekends: Option<bool>,
+    first_n_days: Option<u32>,
+    max_commits: Option<usize>,
+    max_scan: Option<usize>,
+    timezones: Option<bool>,
+    by_extension: Option<bool>,
+    export_matrix: Option<String>,
+    follow: Option<String>,
+    skip_root: Option<bool>,
+    ignore_reverts: Option<bool>,
+    merge_handling: Option<MergeHandling>,
+    include_reflog: Option<bool>,
+    normalize_github_noreply: Option<bool>,
+    split_by_name: Option<bool>,
+    group_by: Option<GroupBy>,
+    inactive_days: Option<i64>,
+    team: Option<String>,
+    mark_me: Option<bool>,
+    bars: Option<bool>,
+    locale: Option<String>,
+    columns: Option<Vec<Column>>,
+    compact: Option<bool>,
+    sparkline: Option<bool>,
+    files_touched: Option<bool>,
+    files_changed: Option<bool>,
+    skip_status: Option<bool>,
+    retries: Option<u32>,
+    aliases: Option<bool>,
+    tags: Option<bool>,
+    longest_gap: Option<bool>,
+    weekly_rate: Option<bool>,
+    trend: Option<bool>,
+    spread: Option<bool>,
+    weight_ext: Option<String>,
+    alert_low: Option<f64>,
+    alert_high: Option<f64>,
+    verbose: Option<bool>,
+    quiet: Option<bool>,
+}
+
+impl FileConfig {
+    pub fn load(path: &str) -> Result<FileConfig, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Could not read the config file '{}': {}", path, e))?;
+
+        toml::from_str(&contents).map_err(|e| format!("Invalid config file '{}': {}", path, e))
+    }
+
+    /// Fill in every option that was not given on the command line; a switch turned on in the file
+    /// can be turned off again with its `--no-<flag>` form
+    pub fn apply_to(self, args: &mut Args) {
+        args.path = args.path.take().or(self.path);
+        args.width = args.width.or(self.width);
+        args.analyze_messages = merge_switch(
+            args.analyze_messages,
+            args.no_analyze_messages,
+            self.analyze_messages,
+        );
+        args.top_words = args.top_words.or(self.top_words);
+        args.range = args.range.take().or(self.range);
+        args.head = args.head.take().or(self.head);
+        args.since = args.since.or(self.since);
+        args.until = args.until.or(self.until);
+        args.exclude_weekends = merge_switch(
+            args.exclude_weekends,
+            args.no_exclude_weekends,
+            self.exclude_weekends,
+        );
+        args.first_n_days = args.first_n_days.or(self.first_n_days);
+        args.max_commits = args.max_commits.or(self.max_commits);
+        args.max_scan = args.max_scan.or(self.max_scan);
+        args.timezones = merge_switch(args.timezones, args.no_timezones, self.timezones);
+        args.by_extension =
+            merge_switch(args.by_extension, args.no_by_extension, self.by_extension);
+        args.export_matrix = args.export_matrix.take().or(self.export_matrix);
+        args.follow = args.follow.take().or(self.follow);
+        args.skip_root = merge_switch(args.skip_root, args.no_skip_root, self.skip_root);
+        args.ignore_reverts = merge_switch(
+            args.ignore_reverts,
+            args.no_ignore_reverts,
+            self.ignore_reverts,
+        );
+        args.merge_handling = args.merge_handling.or(self.merge_handling);
+        args.include_reflog = merge_switch(
+            args.include_reflog,
+            args.no_include_reflog,
+            self.include_reflog,
+        );
+        args.normalize_github_noreply = merge_switch(
+            args.normalize_github_noreply,
+            args.no_normalize_github_noreply,
+            self.normalize_github_noreply,
+        );
+        args.split_by_name = merge_switch(
+            args.split_by_name,
+            args.no_split_by_name,
+            self.split_by_name,
+        );
+        args.group_by = args.group_by.or(self.group_by);
+        args.inactive_days = args.inactive_days.or(self.inactive_days);
+        args.team = args.team.take().or(self.team);
+        args.mark_me = merge_switch(args.mark_me, args.no_mark_me, self.mark_me);
+        args.bars = merge_switch(args.bars, args.no_bars, self.bars);
+        args.locale = args.locale.take().or(self.locale);
+        if args.columns.is_empty() {
+            args.columns = self.columns.unwrap_or_default();
+        }
+        args.compact = merge_switch(args.compact, args.no_compact, self.compact);
+        args.sparkline = merge_switch(args.sparkline, args.no_sparkline, self.sparkline);
+        args.files_touched = merge_switch(
+            args.files_touched,
+            args.no_files_touched,
+            self.files_touched,
+        );
+        args.files_changed = merge_switch(
+            args.files_changed,
+            args.no_files_changed,
+            self.files_changed,
+        );
+        args.skip_status = merge_switch(args.skip_status, args.no_skip_status, self.skip_status);
+        args.retries = args.retries.or(self.retries);
+        args.aliases = merge_switch(args.aliases, args.no_aliases, self.aliases);
+        args.tags = merge_switch(args.tags, args.no_tags, self.tags);
+        args.longest_gap = merge_switch(args.longest_gap, args.no_longest_gap, self.longest_gap);
+        args.weekly_rate = merge_switch(args.weekly_rate, args.no_weekly_rate, self.weekly_rate);
+        args.trend = merge_switch(args.trend, args.no_trend, self.trend);
+        args.spread = merge_switch(args.spread, args.no_spread, self.spread);
+        args.weight_ext = args.weight_ext.take().or(self.weight_ext);
+        args.alert_low = args.alert_low.or(self.alert_low);
+        args.alert_high = args.alert_high.or(self.alert_high);
+        // The two conflict, so giving one on the command line drops the other from the file
+        let (verbose, quiet) = (args.verbose, args.quiet);
+        args.verbose = merge_switch(verbose, args.no_verbose || quiet, self.verbose);
+        args.quiet = merge_switch(quiet, args.no_quiet || verbose, self.quiet);
+    }
+}
+
+/// A switch is on when given on the command line, or set in the file and not negated
+fn merge_switch(flag: bool, negated: bool, file_value: Option<bool>) -> bool {
+    flag || (!negated && file_value.unwrap_or(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn test_file_values_fill_missing_flags() {
+        let file_config: FileConfig =
+            toml::from_str("max-commits = 500\nsince = \"2023-01-01\"\nbars = true").unwrap();
+
+        let mut args = Args::parse_from(["git_history_explorer"]);
+        file_config.apply_to(&mut args);
+
+        assert_eq!(args.max_commits, Some(500));
+        assert_eq!(args.since, NaiveDate::from_ymd_opt(2023, 1, 1));
+        assert!(args.bars);
+    }
+
+    #[test]
+    fn test_flags_override_file_values() {
+        let file_config: FileConfig =
+            toml::from_str("max-commits = 500\nsince = \"2023-01-01\"").unwrap();
+
+        let mut args = Args::parse_from(["git_history_explorer", "--max-commits", "20"]);
+        file_config.apply_to(&mut args);
+
+        assert_eq!(args.max_commits, Some(20));
+        assert_eq!(args.since, NaiveDate::from_ymd_opt(2023, 1, 1));
+    }
+
+    #[test]
+    fn test_negated_flags_override_file_switches() {
+        let file_config: FileConfig = toml::from_str("bars = true\nskip-root = true").unwrap();
+
+        let mut args = Args::parse_from(["git_history_explorer", "--no-bars"]);
+        file_config.apply_to(&mut args);
+
+        assert!(!args.bars);
+        assert!(args.skip_root);
+
+        let args = Args::parse_from(["git_history_explorer", "--no-bars", "--bars"]);
+        assert!(args.bars && !args.no_bars);
+        let args = Args::parse_from(["git_history_explorer", "--bars", "--no-bars"]);
+        assert!(!args.bars && args.no_bars);
+    }
+
+    #[test]
+    fn test_verbose_and_quiet_on_the_command_line_override_the_file() {
+        let file_config: FileConfig = toml::from_str("quiet = true").unwrap();
+        let mut args = Args::parse_from(["git_history_explorer", "--verbose"]);
+        file_config.apply_to(&mut args);
+        assert!(args.verbose && !args.quiet);
+
+        let file_config: FileConfig = toml::from_str("verbose = true").unwrap();
+        let mut args = Args::parse_from(["git_history_explorer", "--quiet"]);
+        file_config.apply_to(&mut args);
+        assert!(args.quiet && !args.verbose);
+    }
+
+    #[test]
+    fn test_invalid_config_is_rejected() {
+        assert!(toml::from_str::<FileConfig>("max-comits = 500").is_err());
+        assert!(toml::from_str::<FileConfig>("since = \"yesterday\"").is_err());
+    }
+}
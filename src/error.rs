@@ -0,0 +1,103 @@
+use chrono::NaiveDate;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Ways collecting commit statistics from a repository can fail
+#[derive(Debug)]
+pub enum RepositoryError {
+    /// No repository could be opened at the given path
+    NotFound { path: String, source: git2::Error },
+    /// The repository has no commits yet
+    EmptyRepository,
+    /// A `--range` value that is not of the form `<from>..<to>`
+    InvalidRange(String),
+    /// A revision that does not resolve to a commit
+    InvalidRevision {
+        revision: String,
+        source: git2::Error,
+    },
+    /// Two options that cannot be used together
+    ConflictingOptions(&'static str, &'static str),
+    /// A `--since` date that falls after the `--until` date
+    InvalidDateRange { since: NaiveDate, until: NaiveDate },
+    /// A `--first-n-days` window that ends past the latest representable date
+    FirstDaysOutOfRange(u32),
+    /// The `--export-matrix` file could not be written
+    Export { path: String, source: io::Error },
+    /// The report could not be printed, e.g. because `--width` is too narrow
+    Output(io::Error),
+    /// Any other failure while reading the repository
+    Git(git2::Error),
+}
+
+impl RepositoryError {
+    /// Whether the failure may go away on its own, such as another git process holding a lock
+    pub fn is_transient(&self) -> bool {
+        match self {
+            RepositoryError::NotFound { source, .. } | RepositoryError::Git(source) => {
+                source.code() == git2::ErrorCode::Locked
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for RepositoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RepositoryError::NotFound { path, source } => write!(
+                f,
+                "Could not open the Git repository at '{}'.\nDetails: {}",
+                path, source
+            ),
+            RepositoryError::EmptyRepository => write!(f, "The repository has no commits yet"),
+            RepositoryError::InvalidRange(range) => write!(
+                f,
+                "Invalid range '{}', expected the form '<from>..<to>'",
+                range
+            ),
+            RepositoryError::InvalidRevision { revision, source } => {
+                write!(f, "Could not resolve revision '{}': {}", revision, source)
+            }
+            RepositoryError::ConflictingOptions(first, second) => {
+                write!(f, "The {} and {} options cannot be combined", first, second)
+            }
+            RepositoryError::InvalidDateRange { since, until } => write!(
+                f,
+                "The --since date ({}) is after the --until date ({})",
+                since, until
+            ),
+            RepositoryError::FirstDaysOutOfRange(days) => write!(
+                f,
+                "--first-n-days {} reaches past the latest supported date",
+                days
+            ),
+            RepositoryError::Export { path, source } => {
+                write!(f, "Could not write the matrix to '{}': {}", path, source)
+            }
+            RepositoryError::Output(source) => write!(f, "Could not print the report: {}", source),
+            RepositoryError::Git(source) => write!(f, "{}", source),
+        }
+    }
+}
+
+impl Error for RepositoryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RepositoryError::NotFound { source, .. }
+            | RepositoryError::InvalidRevision { source, .. }
+            | RepositoryError::Git(source) => Some(source),
+            RepositoryError::Export { source, .. } | RepositoryError::Output(source) => {
+                Some(source)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<git2::Error> for RepositoryError {
+    fn from(error: git2::Error) -> Self {
+        RepositoryError::Git(error)
+    }
+}
@@ -0,0 +1,93 @@
+use chrono::NaiveDate;
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+/// Quote a CSV field when it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+/// Write one row per commit date and one column per author, with commit counts in the cells
+fn write_matrix<W: Write>(
+    out: &mut W,
+    commits_by_day: &HashMap<String, HashMap<NaiveDate, u32>>,
+) -> io::Result<()> {
+    let mut authors: Vec<&String> = commits_by_day.keys().collect();
+    authors.sort();
+
+    let dates: BTreeSet<&NaiveDate> = commits_by_day.values().flat_map(|d| d.keys()).collect();
+
+    let header: Vec<String> = std::iter::once("date".to_string())
+        .chain(authors.iter().map(|author| csv_field(author)))
+        .collect();
+    writeln!(out, "{}", header.join(","))?;
+
+    for date in dates {
+        let row: Vec<String> = std::iter::once(date.format("%Y-%m-%d").to_string())
+            .chain(authors.iter().map(|author| {
+                commits_by_day[*author]
+                    .get(date)
+                    .copied()
+                    .unwrap_or(0)
+                    .to_string()
+            }))
+            .collect();
+        writeln!(out, "{}", row.join(","))?;
+    }
+
+    Ok(())
+}
+
+pub fn export_matrix(
+    path: &str,
+    commits_by_day: &HashMap<String, HashMap<NaiveDate, u32>>,
+) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write_matrix(&mut out, commits_by_day)?;
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_matrix() {
+        let date = |day| NaiveDate::from_ymd_opt(2023, 1, day).unwrap();
+        let commits_by_day = HashMap::from([
+            (
+                "bob@example.com".to_string(),
+                HashMap::from([(date(2), 3), (date(5), 1)]),
+            ),
+            (
+                "alice@example.com".to_string(),
+                HashMap::from([(date(2), 2)]),
+            ),
+        ]);
+
+        let mut output = Vec::new();
+        write_matrix(&mut output, &commits_by_day).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert_eq!(
+            output.lines().collect::<Vec<_>>(),
+            [
+                "date,alice@example.com,bob@example.com",
+                "2023-01-02,2,3",
+                "2023-01-05,0,1",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("alice@example.com"), "alice@example.com");
+        assert_eq!(csv_field("Smith, Alice"), "\"Smith, Alice\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Label grouping files by extension, such as `.rs`, or `(none)` for files without one
+pub fn extension_label(path: &Path) -> String {
+    match path.extension() {
+        Some(extension) => format!(".{}", extension.to_string_lossy().to_lowercase()),
+        None => "(none)".to_string(),
+    }
+}
+
+/// Normalize an extension given on the command line, e.g. `RS` or `.rs`, to its label `.rs`
+pub fn extension_from_arg(extension: &str) -> String {
+    format!(".{}", extension.trim_start_matches('.').to_lowercase())
+}
+
+pub fn print_extensions(commits_by_extension: &HashMap<String, u32>) {
+    let mut extensions: Vec<(&String, &u32)> = commits_by_extension.iter().collect();
+    extensions.sort_by(|(a_extension, a_count), (b_extension, b_count)| {
+        b_count.cmp(a_count).then(a_extension.cmp(b_extension))
+    });
+
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    let result = writeln!(stdout, "\n{:<20} {:<10}", "Extension", "Commits").and_then(|_| {
+        extensions
+            .into_iter()
+            .try_for_each(|(extension, count)| writeln!(stdout, "{:<20} {:<10}", extension, count))
+    });
+
+    if let Err(e) = result {
+        if e.kind() != io::ErrorKind::BrokenPipe {
+            eprintln!("Error writing to stdout: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extension_label() {
+        assert_eq!(extension_label(Path::new("src/main.rs")), ".rs");
+        assert_eq!(extension_label(Path::new("docs/README.MD")), ".md");
+        assert_eq!(extension_label(Path::new("Makefile")), "(none)");
+        assert_eq!(extension_label(Path::new(".gitignore")), "(none)");
+    }
+
+    #[test]
+    fn test_extension_from_arg() {
+        assert_eq!(extension_from_arg("rs"), ".rs");
+        assert_eq!(extension_from_arg(".MD"), ".md");
+    }
+}
@@ -0,0 +1,49 @@
+const GITHUB_NOREPLY_DOMAIN: &str = "@users.noreply.github.com";
+
+/// Reduce a GitHub no-reply address (`12345+user@users.noreply.github.com` or
+/// `user@users.noreply.github.com`) to the bare username, leaving other emails untouched
+pub fn normalize_github_noreply(email: &str) -> String {
+    let Some(local) = email.strip_suffix(GITHUB_NOREPLY_DOMAIN) else {
+        return email.to_owned();
+    };
+
+    match local.split_once('+') {
+        Some((id, username)) if id.chars().all(|c| c.is_ascii_digit()) => username.to_owned(),
+        _ => local.to_owned(),
+    }
+}
+
+/// The lowercased domain of `email`, or the whole value when it has no `@`
+pub fn email_domain(email: &str) -> String {
+    match email.rsplit_once('@') {
+        Some((_, domain)) => domain.to_lowercase(),
+        None => email.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_github_noreply() {
+        assert_eq!(
+            normalize_github_noreply("12345+octocat@users.noreply.github.com"),
+            "octocat"
+        );
+        assert_eq!(
+            normalize_github_noreply("octocat@users.noreply.github.com"),
+            "octocat"
+        );
+        assert_eq!(
+            normalize_github_noreply("octocat@example.com"),
+            "octocat@example.com"
+        );
+    }
+
+    #[test]
+    fn test_email_domain() {
+        assert_eq!(email_domain("alice@Example.COM"), "example.com");
+        assert_eq!(email_domain("octocat"), "octocat");
+    }
+}
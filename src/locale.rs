@@ -0,0 +1,124 @@
+/// Number and date conventions used when rendering the author table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    /// Separator between groups of three digits, if any
+    pub thousands_separator: Option<char>,
+    pub decimal_separator: char,
+    /// `chrono` format string for dates
+    pub date_format: &'static str,
+}
+
+impl Default for Locale {
+    /// Ungrouped numbers and month/day/year dates, as printed without `--locale`
+    fn default() -> Self {
+        Locale {
+            thousands_separator: None,
+            decimal_separator: '.',
+            date_format: "%m/%d/%Y",
+        }
+    }
+}
+
+impl Locale {
+    /// Look up a locale tag such as `de`, `en-GB` or `fr_FR.UTF-8`, falling back from the
+    /// region to the language; `None` when neither is known
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let tag = tag
+            .split('.')
+            .next()
+            .unwrap_or_default()
+            .replace('_', "-")
+            .to_lowercase();
+        let language = tag.split('-').next().unwrap_or_default();
+
+        Self::lookup(&tag).or_else(|| Self::lookup(language))
+    }
+
+    fn lookup(tag: &str) -> Option<Locale> {
+        let (thousands_separator, decimal_separator, date_format) = match tag {
+            "en" | "en-us" => (',', '.', "%m/%d/%Y"),
+            "en-gb" | "en-au" | "en-ie" | "en-nz" => (',', '.', "%d/%m/%Y"),
+            "en-ca" => (',', '.', "%Y-%m-%d"),
+            "de" | "de-de" | "de-at" => ('.', ',', "%d.%m.%Y"),
+            "de-ch" => ('\'', '.', "%d.%m.%Y"),
+            "es" | "it" | "pt" => ('.', ',', "%d/%m/%Y"),
+            "nl" => ('.', ',', "%d-%m-%Y"),
+            "da" | "nb" | "no" | "fi" => (' ', ',', "%d.%m.%Y"),
+            "fr" | "fr-fr" | "fr-ca" => (' ', ',', "%d/%m/%Y"),
+            "sv" | "pl" | "cs" => (' ', ',', "%Y-%m-%d"),
+            "ja" | "zh" | "ko" => (',', '.', "%Y/%m/%d"),
+            _ => return None,
+        };
+
+        Some(Locale {
+            thousands_separator: Some(thousands_separator),
+            decimal_separator,
+            date_format,
+        })
+    }
+
+    /// Render `count` with the thousands separator, e.g. `12,345`
+    pub fn format_number(&self, count: i64) -> String {
+        let Some(separator) = self.thousands_separator else {
+            return count.to_string();
+        };
+        let digits = count.unsigned_abs().to_string();
+
+        let mut formatted = String::with_capacity(digits.len() + digits.len() / 3 + 1);
+        if count < 0 {
+            formatted.push('-');
+        }
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                formatted.push(separator);
+            }
+            formatted.push(digit);
+        }
+        formatted
+    }
+
+    /// Replace the decimal point in an already formatted number, e.g. `1.5k`
+    pub fn localize_decimal(&self, formatted: String) -> String {
+        if self.decimal_separator == '.' {
+            formatted
+        } else {
+            formatted.replace('.', &self.decimal_separator.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thousands_separator_follows_locale() {
+        assert_eq!(Locale::default().format_number(1_234_567), "1234567");
+        assert_eq!(
+            Locale::from_tag("en-US").unwrap().format_number(1_234_567),
+            "1,234,567"
+        );
+        assert_eq!(
+            Locale::from_tag("de_DE.UTF-8")
+                .unwrap()
+                .format_number(1_234_567),
+            "1.234.567"
+        );
+        assert_eq!(
+            Locale::from_tag("fr").unwrap().format_number(12_345),
+            "12 345"
+        );
+        assert_eq!(Locale::from_tag("de").unwrap().format_number(999), "999");
+        assert_eq!(
+            Locale::from_tag("en").unwrap().format_number(-1_234),
+            "-1,234"
+        );
+    }
+
+    #[test]
+    fn test_region_falls_back_to_language() {
+        assert_eq!(Locale::from_tag("it-CH"), Locale::from_tag("it"));
+        assert_eq!(Locale::from_tag("en-GB").unwrap().date_format, "%d/%m/%Y");
+        assert_eq!(Locale::from_tag("xx-YY"), None);
+    }
+}
@@ -0,0 +1,53 @@
+use log::Level;
+use std::io::Write;
+
+/// Filter used when `RUST_LOG` is not set: notes and warnings, plus timings with `verbose`, or
+/// only errors with `quiet`
+fn default_filter(quiet: bool, verbose: bool) -> &'static str {
+    if quiet {
+        "error"
+    } else if verbose {
+        "debug"
+    } else {
+        "info"
+    }
+}
+
+/// Prefix each stderr line starts with, e.g. `Warning: Skipping commit …`
+fn label(level: Level) -> &'static str {
+    match level {
+        Level::Error => "Error",
+        Level::Warn => "Warning",
+        Level::Info => "Note",
+        Level::Debug => "Debug",
+        Level::Trace => "Trace",
+    }
+}
+
+/// Send log records to stderr as `<label>: <message>`, filtered by `RUST_LOG` when it is set
+pub fn init(quiet: bool, verbose: bool) {
+    env_logger::Builder::from_env(
+        env_logger::Env::default().default_filter_or(default_filter(quiet, verbose)),
+    )
+    .format(|buf, record| writeln!(buf, "{}: {}", label(record.level()), record.args()))
+    .init();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_filter() {
+        assert_eq!(default_filter(false, false), "info");
+        assert_eq!(default_filter(false, true), "debug");
+        assert_eq!(default_filter(true, false), "error");
+        assert_eq!(default_filter(true, true), "error");
+    }
+
+    #[test]
+    fn test_label() {
+        assert_eq!(label(Level::Warn), "Warning");
+        assert_eq!(label(Level::Info), "Note");
+    }
+}
@@ -1,14 +1,128 @@
 mod cli;
+mod config;
+mod error;
+mod export;
+mod extensions;
+mod identity;
+mod locale;
+mod logging;
+mod messages;
 mod repository;
+mod table;
+mod team;
+mod timezones;
 mod user_commit_info;
+mod velocity;
 
 use cli::Args;
-use repository::get_status;
+use config::FileConfig;
+use extensions::extension_from_arg;
+use locale::Locale;
+use repository::{get_status, RepositoryConfig};
+use table::TableOptions;
+use team::Team;
 
+use chrono::Utc;
 use clap::Parser;
+use log::{error, warn};
+use std::process::ExitCode;
 
-fn main() {
-    let args = Args::parse();
-    let repo_path = &args.path;
-    get_status(repo_path);
+fn main() -> ExitCode {
+    let mut args = Args::parse();
+    if let Some(config_path) = &args.config {
+        match FileConfig::load(config_path) {
+            Ok(file_config) => file_config.apply_to(&mut args),
+            Err(e) => {
+                // The logger isn't set up yet, since the file may set `quiet` or `verbose`
+                eprintln!("Error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    logging::init(args.quiet, args.verbose);
+
+    let locale = match args.locale.as_deref() {
+        Some(tag) => Locale::from_tag(tag).unwrap_or_else(|| {
+            warn!("Unknown locale '{}'; using the default formatting", tag);
+            Locale::default()
+        }),
+        None => Locale::default(),
+    };
+
+    let team = match args.team.as_deref().map(Team::load).transpose() {
+        Ok(team) => team,
+        Err(e) => {
+            error!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let weight_ext = args.weight_ext.as_deref().map(extension_from_arg);
+
+    let repo_path = args.path.as_deref();
+    let config = RepositoryConfig {
+        top_words: args
+            .analyze_messages
+            .then_some(args.top_words.unwrap_or(10)),
+        range: args.range,
+        head: args.head,
+        since: args.since,
+        until: args.until,
+        exclude_weekends: args.exclude_weekends,
+        max_commits: args.max_commits,
+        max_scan: args.max_scan,
+        timezones: args.timezones,
+        follow: args.follow,
+        skip_root: args.skip_root,
+        ignore_reverts: args.ignore_reverts,
+        merge_handling: args.merge_handling.unwrap_or_default(),
+        include_reflog: args.include_reflog,
+        files_touched: args.files_touched,
+        files_changed: args.files_changed,
+        by_extension: args.by_extension,
+        aliases: args.aliases,
+        weight_ext: weight_ext.clone(),
+        monthly_counts: args.sparkline || args.alert_low.is_some() || args.alert_high.is_some(),
+        commit_days: args.longest_gap || args.trend || args.spread,
+        first_n_days: args.first_n_days,
+        normalize_github_noreply: args.normalize_github_noreply,
+        split_by_name: args.split_by_name,
+        skip_status: args.skip_status,
+        export_matrix: args.export_matrix,
+        retries: args.retries.unwrap_or(3),
+        group_by: args.group_by.unwrap_or_default(),
+        mark_me: args.mark_me,
+        tags: args.tags,
+        alert_low: args.alert_low,
+        alert_high: args.alert_high,
+    };
+    let table_options = TableOptions {
+        width: args.width,
+        inactive_days: args.inactive_days,
+        today: Utc::now().date_naive(),
+        bars: args.bars,
+        files_touched: args.files_touched,
+        files_changed: args.files_changed,
+        aliases: args.aliases,
+        compact: args.compact,
+        tags: args.tags,
+        message_lengths: args.analyze_messages,
+        longest_gap: args.longest_gap,
+        weekly_rate: args.weekly_rate,
+        trend: args.trend,
+        spread: args.spread,
+        weight_ext,
+        sparkline: args.sparkline,
+        current_user: None,
+        locale,
+        team,
+        column_order: args.columns,
+    };
+    match get_status(repo_path, &config, table_options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            error!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "from", "has", "have", "if",
+    "in", "into", "is", "it", "its", "no", "not", "of", "on", "or", "so", "that", "the", "then",
+    "this", "to", "was", "were", "when", "with",
+];
+
+/// Split a commit message into lowercase words, dropping punctuation
+pub fn tokenize(message: &str) -> impl Iterator<Item = String> + '_ {
+    message
+        .split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|word| word.trim_matches('\'').to_lowercase())
+        .filter(|word| !word.is_empty())
+}
+
+pub fn is_stopword(word: &str) -> bool {
+    STOPWORDS.contains(&word)
+}
+
+pub fn count_words(message: &str, word_counts: &mut HashMap<String, u32>) {
+    for word in tokenize(message).filter(|word| !is_stopword(word)) {
+        *word_counts.entry(word).or_insert(0) += 1;
+    }
+}
+
+/// Length of `message` in characters, ignoring surrounding whitespace
+pub fn message_length(message: &str) -> usize {
+    message.trim().chars().count()
+}
+
+/// Whether `message` is a revert, as written by `git revert`
+pub fn is_revert(message: &str) -> bool {
+    message.starts_with("Revert \"")
+        || message
+            .lines()
+            .any(|line| line.trim_start().starts_with("This reverts commit "))
+}
+
+fn top_words(word_counts: &HashMap<String, u32>, limit: usize) -> Vec<(&str, u32)> {
+    let mut words: Vec<(&str, u32)> = word_counts
+        .iter()
+        .map(|(word, count)| (word.as_str(), *count))
+        .collect();
+
+    words.sort_by(|(a_word, a_count), (b_word, b_count)| {
+        b_count.cmp(a_count).then(a_word.cmp(b_word))
+    });
+    words.truncate(limit);
+    words
+}
+
+pub fn print_top_words(word_counts: &HashMap<String, u32>, limit: usize) {
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    let result = writeln!(stdout, "\n{:<30} {:<10}", "Word", "Count").and_then(|_| {
+        top_words(word_counts, limit)
+            .into_iter()
+            .try_for_each(|(word, count)| writeln!(stdout, "{:<30} {:<10}", word, count))
+    });
+
+    if let Err(e) = result {
+        if e.kind() != io::ErrorKind::BrokenPipe {
+            eprintln!("Error writing to stdout: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        let words: Vec<String> = tokenize("Fix: the parser's (broken) error-handling!").collect();
+        assert_eq!(
+            words,
+            ["fix", "the", "parser's", "broken", "error", "handling"]
+        );
+    }
+
+    #[test]
+    fn test_count_words_skips_stopwords() {
+        let mut word_counts = HashMap::new();
+        for message in [
+            "Fix the parser",
+            "Refactor parser for speed",
+            "Fix typo in README",
+        ] {
+            count_words(message, &mut word_counts);
+        }
+
+        assert_eq!(word_counts.get("parser"), Some(&2));
+        assert_eq!(word_counts.get("fix"), Some(&2));
+        assert_eq!(word_counts.get("readme"), Some(&1));
+        assert!(!word_counts.contains_key("the"));
+        assert!(!word_counts.contains_key("for"));
+
+        assert_eq!(top_words(&word_counts, 2), [("fix", 2), ("parser", 2)]);
+    }
+}
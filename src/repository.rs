@@ -1,89 +1,2372 @@
+use chrono::Datelike;
+use chrono::NaiveDate;
 use chrono::TimeZone;
 use chrono::Utc;
-use git2::Repository;
-use std::collections::HashMap;
-use std::io::{self, Write};
-use std::path::Path;
+use chrono::Weekday;
+use git2::{
+    Commit, Diff, DiffOptions, ErrorCode, Patch, Repository, Revwalk, Signature, StatusOptions,
+};
+use log::{debug, info, log, warn, Level};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
 
+use crate::error::RepositoryError;
+use crate::export::export_matrix;
+use crate::extensions::{extension_label, print_extensions};
+use crate::identity::{email_domain, normalize_github_noreply};
+use crate::messages::{count_words, is_revert, message_length, print_top_words};
+use crate::table::{print_commits, TableOptions};
+use crate::team::Team;
+use crate::timezones::print_timezones;
 use crate::user_commit_info::UserCommitInfo;
+use crate::velocity::check_velocity;
 
-fn collect_commit_info(repo: Repository) -> Vec<(String, UserCommitInfo)> {
-    let mut revwalk = repo
-        .revwalk()
-        .expect("Could not access the repository's commits");
+/// Options controlling how commits are collected from the repository and reported
+#[derive(Debug, Default)]
+pub struct RepositoryConfig {
+    /// Number of most frequent commit-message words to report, if any
+    pub top_words: Option<usize>,
+    /// Only count commits reachable from B but not from A, given as `A..B`
+    pub range: Option<String>,
+    /// Walk history from this revision instead of `HEAD`
+    pub head: Option<String>,
+    /// Ignore commits made before this date
+    pub since: Option<NaiveDate>,
+    /// Ignore commits made after this date
+    pub until: Option<NaiveDate>,
+    /// Ignore commits made on a Saturday or Sunday (UTC)
+    pub exclude_weekends: bool,
+    /// Stop once this many commits have been counted
+    pub max_commits: Option<usize>,
+    /// Stop once this many commits have been walked, whether counted or not
+    pub max_scan: Option<usize>,
+    /// Tally commits by their author's UTC offset
+    pub timezones: bool,
+    /// Only count commits that change files under this path
+    pub follow: Option<String>,
+    /// Ignore root commits, such as an initial import
+    pub skip_root: bool,
+    /// Ignore commits that revert another commit
+    pub ignore_reverts: bool,
+    /// Whether merge commits are counted, skipped or credited to one row
+    pub merge_handling: MergeHandling,
+    /// Also count commits from the local HEAD reflog that no ref reaches any more
+    pub include_reflog: bool,
+    /// Count the distinct files each author has changed
+    pub files_touched: bool,
+    /// Sum the number of files each author's commits changed
+    pub files_changed: bool,
+    /// Tally commits by the extensions of the files they change
+    pub by_extension: bool,
+    /// Count the distinct names each author has committed under
+    pub aliases: bool,
+    /// Sum the lines each author added or deleted in files with this extension, e.g. `.rs`
+    pub weight_ext: Option<String>,
+    /// Keep per-month commit counts for each author, for sparklines and velocity alerts
+    pub monthly_counts: bool,
+    /// Keep each author's commit counts per day, for the longest gap, trend and spread
+    pub commit_days: bool,
+    /// Only count commits made within this many days of the earliest commit
+    pub first_n_days: Option<u32>,
+    /// Reduce GitHub no-reply addresses to the bare username so variants merge
+    pub normalize_github_noreply: bool,
+    /// Treat each distinct name under an email as a separate author
+    pub split_by_name: bool,
+    /// Don't check the working tree for uncommitted changes
+    pub skip_status: bool,
+    /// Write a date-by-author CSV of commit counts to this path
+    pub export_matrix: Option<String>,
+    /// How many more times to try when the repository is locked by another process
+    pub retries: u32,
+    /// What each row of the report stands for
+    pub group_by: GroupBy,
+    /// Mark the row of the author configured as `user.email`
+    pub mark_me: bool,
+    /// Count the annotated tags each author created
+    pub tags: bool,
+    /// Warn when last month's commits fall below this multiple of the trailing average
+    pub alert_low: Option<f64>,
+    /// Warn when last month's commits rise above this multiple of the trailing average
+    pub alert_high: Option<f64>,
+}
+
+/// The key commits are aggregated under
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GroupBy {
+    /// One row per author email
+    #[default]
+    Email,
+    /// One row per email domain, e.g. `example.com`
+    Domain,
+}
+
+/// How merge commits are attributed
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MergeHandling {
+    /// Credit merges to their author like any other commit
+    #[default]
+    Count,
+    /// Ignore merges
+    Skip,
+    /// Credit all merges to a single `(merges)` row
+    Bucket,
+}
+
+/// Row that merges are credited to with `MergeHandling::Bucket`
+const MERGES_BUCKET: &str = "(merges)";
+
+/// The set of commits to walk, resolved from the scope options
+#[derive(Debug, PartialEq, Eq)]
+enum Scope<'a> {
+    Head,
+    Revision(&'a str),
+    Range(&'a str, &'a str),
+}
 
-    revwalk.push_head().expect("Could not find HEAD");
+impl fmt::Display for Scope<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Scope::Head => write!(f, "commits reachable from HEAD"),
+            Scope::Revision(revision) => write!(f, "commits reachable from {}", revision),
+            Scope::Range(from, to) => {
+                write!(f, "commits reachable from {} but not from {}", to, from)
+            }
+        }
+    }
+}
 
-    let mut commit_info_map: HashMap<String, UserCommitInfo> = HashMap::new();
+impl RepositoryConfig {
+    /// Resolve the scope options, which are mutually exclusive
+    fn scope(&self) -> Result<Scope<'_>, RepositoryError> {
+        match (&self.head, &self.range) {
+            (Some(_), Some(_)) => Err(RepositoryError::ConflictingOptions("--head", "--range")),
+            (Some(head), None) => Ok(Scope::Revision(head)),
+            (None, Some(range)) => {
+                let (from, to) = parse_range(range)?;
+                Ok(Scope::Range(from, to))
+            }
+            (None, None) => Ok(Scope::Head),
+        }
+    }
 
-    for commit_oid in revwalk {
-        let commit_oid = commit_oid.expect("Invalid commit");
-        let commit = repo.find_commit(commit_oid).expect("Could not find commit");
+    fn validate(&self) -> Result<(), RepositoryError> {
+        match self.scope()? {
+            Scope::Revision(_) if self.include_reflog => {
+                return Err(RepositoryError::ConflictingOptions(
+                    "--include-reflog",
+                    "--head",
+                ));
+            }
+            Scope::Range(..) if self.include_reflog => {
+                return Err(RepositoryError::ConflictingOptions(
+                    "--include-reflog",
+                    "--range",
+                ));
+            }
+            _ => {}
+        }
 
-        let email = commit.author().email().map(|s| s.to_owned());
-        if let Some(email) = email {
-            let commit_time = Utc.timestamp_opt(commit.time().seconds(), 0);
-            if let chrono::LocalResult::Single(commit_time) = commit_time {
-                commit_info_map
-                    .entry(email)
-                    .and_modify(|c: &mut UserCommitInfo| c.update(commit_time.date_naive()))
-                    .or_insert_with(|| UserCommitInfo::new(commit_time.date_naive()));
+        if let (Some(since), Some(until)) = (self.since, self.until) {
+            if since > until {
+                return Err(RepositoryError::InvalidDateRange { since, until });
             }
         }
+
+        Ok(())
+    }
+
+    fn includes_date(&self, date: NaiveDate) -> bool {
+        let weekend = matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+
+        self.since.is_none_or(|since| date >= since)
+            && self.until.is_none_or(|until| date <= until)
+            && !(self.exclude_weekends && weekend)
     }
+}
 
-    commit_info_map.into_iter().collect()
+struct CommitStats {
+    authors: Vec<(String, UserCommitInfo)>,
+    word_counts: HashMap<String, u32>,
+    timezone_counts: HashMap<i32, u32>,
+    commits_by_day: HashMap<String, HashMap<NaiveDate, u32>>,
+    /// Number of commits changing files with each extension
+    commits_by_extension: HashMap<String, u32>,
+    /// Whether `max_commits` stopped the walk before every commit was seen
+    limited: bool,
 }
 
-fn print_commits(mut commits: Vec<(String, UserCommitInfo)>) {
-    commits.sort_by(|(_, a), (_, b)| {
-        a.first_commit
-            .cmp(&b.first_commit)
-            .then(a.last_commit.cmp(&b.last_commit).reverse())
-    });
+fn parse_range(range: &str) -> Result<(&str, &str), RepositoryError> {
+    match range.split_once("..") {
+        Some((from, to)) if !from.is_empty() && !to.is_empty() && !to.starts_with('.') => {
+            Ok((from, to))
+        }
+        _ => Err(RepositoryError::InvalidRange(range.to_owned())),
+    }
+}
 
-    let stdout = io::stdout();
-    let mut stdout = stdout.lock();
-
-    if let Err(e) = writeln!(
-        stdout,
-        "{:<55} {:<10} {:<12} {:<12} {:<5}",
-        "Email", "Commits", "First", "Last", "Days"
-    ) {
-        eprintln!("Error writing to stdout: {}", e);
-    }
-
-    for (email, user_commit_info) in commits {
-        if let Err(e) = writeln!(
-            stdout,
-            "{:<55} {:<10} {:<12} {:<12} {:<5}",
-            email,
-            user_commit_info.commits,
-            user_commit_info.first_commit.format("%m/%d/%Y"),
-            user_commit_info.last_commit.format("%m/%d/%Y"),
-            user_commit_info.days_between()
-        ) {
-            if e.kind() != io::ErrorKind::BrokenPipe {
-                eprintln!("Error writing to stdout: {}", e);
+/// Resolve `revision` to a commit, peeling annotated and signed tags
+fn resolve_revision(repo: &Repository, revision: &str) -> Result<git2::Oid, RepositoryError> {
+    repo.revparse_single(revision)
+        .and_then(|object| object.peel_to_commit())
+        .map(|commit| commit.id())
+        .map_err(|source| RepositoryError::InvalidRevision {
+            revision: revision.to_owned(),
+            source,
+        })
+}
+
+/// The key an author's commits are aggregated under, given the identity options
+fn author_key(config: &RepositoryConfig, email: &str, name: &str) -> String {
+    let key = email_key(config, email);
+    if config.split_by_name && config.group_by == GroupBy::Email {
+        format!("{} <{}>", name, key)
+    } else {
+        key
+    }
+}
+
+/// The part of the author key that comes from the email, i.e. the key without `--split-by-name`
+fn email_key(config: &RepositoryConfig, email: &str) -> String {
+    let email = if config.normalize_github_noreply {
+        normalize_github_noreply(email)
+    } else {
+        email.to_owned()
+    };
+
+    if config.group_by == GroupBy::Domain {
+        email_domain(&email)
+    } else {
+        email
+    }
+}
+
+/// Undo `author_key`'s `Name <email>` form, leaving what `email_key` returns
+fn key_email<'a>(config: &RepositoryConfig, key: &'a str) -> &'a str {
+    if !config.split_by_name || config.group_by != GroupBy::Email {
+        return key;
+    }
+
+    key.strip_suffix('>')
+        .and_then(|key| key.rsplit_once('<'))
+        .map_or(key, |(_, email)| email)
+}
+
+/// The author keys of the rows whose email is listed in `team`, however authors are keyed
+fn team_members(
+    config: &RepositoryConfig,
+    team: &Team,
+    authors: &[(String, UserCommitInfo)],
+) -> Team {
+    let team = team.map_emails(|email| email_key(config, email));
+    Team::from_emails(
+        authors
+            .iter()
+            .map(|(key, _)| key)
+            .filter(|key| team.contains(key_email(config, key)))
+            .cloned(),
+    )
+}
+
+/// The configured `user.email` (and `user.name`) as an author key, if set
+fn current_user(repo: &Repository, config: &RepositoryConfig) -> Option<String> {
+    let git_config = repo.config().ok()?;
+    let email = git_config.get_string("user.email").ok()?;
+    let name = git_config.get_string("user.name").unwrap_or_default();
+    Some(author_key(config, &email, &name))
+}
+
+/// Diff `commit` against its first parent, limited to `pathspec` when given
+fn diff_to_parent<'a>(
+    repo: &'a Repository,
+    commit: &Commit,
+    pathspec: Option<&str>,
+) -> Result<Diff<'a>, git2::Error> {
+    let tree = commit.tree()?;
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+
+    let mut options = DiffOptions::new();
+    if let Some(pathspec) = pathspec {
+        options.pathspec(pathspec);
+    }
+    repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut options))
+}
+
+/// Whether `commit` changes anything under `path` compared to its first parent.
+/// Merges are never diffed, as with `git log --stat`, so they never touch a path.
+fn touches_path(repo: &Repository, commit: &Commit, path: &str) -> Result<bool, git2::Error> {
+    if commit.parent_count() > 1 {
+        return Ok(false);
+    }
+
+    Ok(diff_to_parent(repo, commit, Some(path))?.deltas().len() > 0)
+}
+
+/// Paths added, modified or deleted by `commit` compared to its first parent; none for merges,
+/// whose first-parent diff would repeat every change brought in from the merged branch
+fn changed_paths(repo: &Repository, commit: &Commit) -> Result<Vec<PathBuf>, git2::Error> {
+    if commit.parent_count() > 1 {
+        return Ok(Vec::new());
+    }
+
+    let diff = diff_to_parent(repo, commit, None)?;
+
+    Ok(diff
+        .deltas()
+        .filter_map(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+        .map(Path::to_path_buf)
+        .collect())
+}
+
+/// Lines added plus lines deleted by `commit` in files labelled `extension`; none for merges
+fn lines_changed_in(
+    repo: &Repository,
+    commit: &Commit,
+    extension: &str,
+) -> Result<usize, git2::Error> {
+    if commit.parent_count() > 1 {
+        return Ok(0);
+    }
+
+    let diff = diff_to_parent(repo, commit, None)?;
+    let mut lines = 0;
+    for (index, delta) in diff.deltas().enumerate() {
+        let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) else {
+            continue;
+        };
+        if extension_label(path) != extension {
+            continue;
+        }
+        if let Some(patch) = Patch::from_diff(&diff, index)? {
+            let (_, additions, deletions) = patch.line_stats()?;
+            lines += additions + deletions;
+        }
+    }
+    Ok(lines)
+}
+
+fn commit_date(commit: &Commit) -> Option<NaiveDate> {
+    time_date(commit.time())
+}
+
+fn time_date(time: git2::Time) -> Option<NaiveDate> {
+    match Utc.timestamp_opt(time.seconds(), 0) {
+        chrono::LocalResult::Single(commit_time) => Some(commit_time.date_naive()),
+        _ => None,
+    }
+}
+
+/// Record a commit that can't be attributed; shown as a warning unless `--quiet` or `RUST_LOG` hides it
+fn log_skipped_commit(commit: &Commit, reason: &str) {
+    warn!("Skipping commit {}: {}", commit.id(), reason);
+}
+
+fn build_revwalk<'a>(
+    repo: &'a Repository,
+    config: &RepositoryConfig,
+) -> Result<Revwalk<'a>, RepositoryError> {
+    let mut revwalk = repo.revwalk()?;
+
+    match config.scope()? {
+        Scope::Range(from, to) => {
+            let from = resolve_revision(repo, from)?;
+            let to = resolve_revision(repo, to)?;
+            revwalk.push(to)?;
+            revwalk.hide(from)?;
+        }
+        Scope::Revision(revision) => {
+            revwalk.push(resolve_revision(repo, revision)?)?;
+        }
+        Scope::Head => match repo.head() {
+            Err(e) if e.code() == ErrorCode::UnbornBranch => {
+                return Err(RepositoryError::EmptyRepository)
+            }
+            Err(e) => return Err(e.into()),
+            Ok(_) => revwalk.push_head()?,
+        },
+    }
+
+    Ok(revwalk)
+}
+
+/// The date range covering the first `days` days after the earliest commit in scope
+fn first_days_window(
+    repo: &Repository,
+    config: &RepositoryConfig,
+    days: u32,
+) -> Result<Option<(NaiveDate, NaiveDate)>, RepositoryError> {
+    let mut earliest: Option<NaiveDate> = None;
+    for commit_oid in build_revwalk(repo, config)? {
+        let commit = repo.find_commit(commit_oid?)?;
+        if let Some(date) = commit_date(&commit) {
+            earliest = Some(earliest.map_or(date, |earliest| earliest.min(date)));
+        }
+    }
+
+    let Some(earliest) = earliest else {
+        return Ok(None);
+    };
+    let end = chrono::TimeDelta::try_days(days.into())
+        .and_then(|window| earliest.checked_add_signed(window))
+        .ok_or(RepositoryError::FirstDaysOutOfRange(days))?;
+
+    Ok(Some((earliest, end)))
+}
+
+/// Per-author totals and report tallies built from the commits that pass every filter
+struct Collector<'a> {
+    repo: &'a Repository,
+    config: &'a RepositoryConfig,
+    window: Option<(NaiveDate, NaiveDate)>,
+    commits_kept: usize,
+    commit_info_map: HashMap<String, UserCommitInfo>,
+    word_counts: HashMap<String, u32>,
+    timezone_counts: HashMap<i32, u32>,
+    paths_by_author: HashMap<String, HashSet<PathBuf>>,
+    files_changed_by_author: HashMap<String, usize>,
+    names_by_author: HashMap<String, HashSet<String>>,
+    commits_by_day: HashMap<String, HashMap<NaiveDate, u32>>,
+    commits_by_extension: HashMap<String, u32>,
+}
+
+impl<'a> Collector<'a> {
+    fn new(
+        repo: &'a Repository,
+        config: &'a RepositoryConfig,
+        window: Option<(NaiveDate, NaiveDate)>,
+    ) -> Self {
+        Collector {
+            repo,
+            config,
+            window,
+            commits_kept: 0,
+            commit_info_map: HashMap::new(),
+            word_counts: HashMap::new(),
+            timezone_counts: HashMap::new(),
+            paths_by_author: HashMap::new(),
+            files_changed_by_author: HashMap::new(),
+            names_by_author: HashMap::new(),
+            commits_by_day: HashMap::new(),
+            commits_by_extension: HashMap::new(),
+        }
+    }
+
+    /// Whether `max_commits` commits have been counted already
+    fn is_full(&self) -> bool {
+        self.config
+            .max_commits
+            .is_some_and(|max| self.commits_kept >= max)
+    }
+
+    /// Count `commit` for `credit` on the date of `time`, unless one of the filters excludes it
+    fn add(
+        &mut self,
+        commit: &Commit,
+        credit: &Signature,
+        time: git2::Time,
+    ) -> Result<(), RepositoryError> {
+        let (repo, config) = (self.repo, self.config);
+
+        if config.skip_root && commit.parent_count() == 0 {
+            return Ok(());
+        }
+
+        if config.ignore_reverts && commit.message().is_some_and(is_revert) {
+            return Ok(());
+        }
+
+        let merge = commit.parent_count() > 1;
+        if merge && config.merge_handling == MergeHandling::Skip {
+            return Ok(());
+        }
+
+        let Some(email) = credit.email() else {
+            log_skipped_commit(commit, "the email is not valid UTF-8");
+            return Ok(());
+        };
+        let email = if merge && config.merge_handling == MergeHandling::Bucket {
+            MERGES_BUCKET.to_owned()
+        } else {
+            author_key(config, email, credit.name().unwrap_or_default())
+        };
+        let Some(commit_date) = time_date(time) else {
+            log_skipped_commit(commit, "the commit time is out of range");
+            return Ok(());
+        };
+
+        if !config.includes_date(commit_date) {
+            return Ok(());
+        }
+
+        if self
+            .window
+            .is_some_and(|(start, end)| commit_date < start || commit_date > end)
+        {
+            return Ok(());
+        }
+
+        if let Some(path) = &config.follow {
+            if !touches_path(repo, commit, path)? {
+                return Ok(());
             }
+        }
+        self.commits_kept += 1;
+
+        if config.aliases {
+            if let Some(name) = credit.name() {
+                self.names_by_author
+                    .entry(email.clone())
+                    .or_default()
+                    .insert(name.to_owned());
+            }
+        }
+
+        if config.export_matrix.is_some() {
+            *self
+                .commits_by_day
+                .entry(email.clone())
+                .or_default()
+                .entry(commit_date)
+                .or_insert(0) += 1;
+        }
+
+        if config.files_touched || config.by_extension || config.files_changed {
+            let paths = changed_paths(repo, commit)?;
+            if config.files_changed {
+                *self
+                    .files_changed_by_author
+                    .entry(email.clone())
+                    .or_insert(0) += paths.len();
+            }
+            if config.by_extension {
+                let extensions: HashSet<String> =
+                    paths.iter().map(|path| extension_label(path)).collect();
+                for extension in extensions {
+                    *self.commits_by_extension.entry(extension).or_insert(0) += 1;
+                }
+            }
+            if config.files_touched {
+                self.paths_by_author
+                    .entry(email.clone())
+                    .or_default()
+                    .extend(paths);
+            }
+        }
+
+        let user_commit_info = self
+            .commit_info_map
+            .entry(email)
+            .and_modify(|c: &mut UserCommitInfo| c.update(commit_date))
+            .or_insert_with(|| UserCommitInfo::new(commit_date));
+
+        if let Some(extension) = &config.weight_ext {
+            user_commit_info.weighted_lines += lines_changed_in(repo, commit, extension)?;
+        }
+
+        if config.monthly_counts {
+            user_commit_info.record_month(commit_date);
+        }
+
+        if config.commit_days {
+            user_commit_info.record_day(commit_date);
+        }
+
+        if config.top_words.is_some() {
+            let message = commit.message().unwrap_or_default();
+            user_commit_info.record_message_length(message_length(message));
+            count_words(message, &mut self.word_counts);
+        }
+
+        if config.timezones {
+            *self
+                .timezone_counts
+                .entry(time.offset_minutes())
+                .or_insert(0) += 1;
+        }
+
+        Ok(())
+    }
+
+    fn finish(self, limited: bool) -> Result<CommitStats, RepositoryError> {
+        let mut commit_info_map = self.commit_info_map;
+
+        for (email, paths) in self.paths_by_author {
+            if let Some(user_commit_info) = commit_info_map.get_mut(&email) {
+                user_commit_info.files_touched = paths.len();
+            }
+        }
+
+        for (email, files_changed) in self.files_changed_by_author {
+            if let Some(user_commit_info) = commit_info_map.get_mut(&email) {
+                user_commit_info.files_changed = files_changed;
+            }
+        }
+
+        for (email, names) in self.names_by_author {
+            if let Some(user_commit_info) = commit_info_map.get_mut(&email) {
+                user_commit_info.aliases = names.len();
+            }
+        }
+
+        debug!(
+            "Counted {} commits by {} authors",
+            self.commits_kept,
+            commit_info_map.len()
+        );
+
+        if self.config.tags {
+            for (email, tags) in tags_by_tagger(self.repo, self.config)? {
+                if let Some(user_commit_info) = commit_info_map.get_mut(&email) {
+                    user_commit_info.tags = tags;
+                }
+            }
+        }
+
+        Ok(CommitStats {
+            authors: commit_info_map.into_iter().collect(),
+            word_counts: self.word_counts,
+            timezone_counts: self.timezone_counts,
+            commits_by_day: self.commits_by_day,
+            commits_by_extension: self.commits_by_extension,
+            limited,
+        })
+    }
+}
+
+fn collect_commit_info(
+    repo: &Repository,
+    config: &RepositoryConfig,
+) -> Result<CommitStats, RepositoryError> {
+    config.validate()?;
+
+    let window = match config.first_n_days {
+        Some(days) => first_days_window(repo, config, days)?,
+        None => None,
+    };
+
+    let revwalk = build_revwalk(repo, config)?;
+
+    let mut collector = Collector::new(repo, config, window);
+    let mut walked_oids: HashSet<git2::Oid> = HashSet::new();
+    let mut limited = false;
+
+    for commit_oid in revwalk.take(config.max_scan.unwrap_or(usize::MAX)) {
+        if collector.is_full() {
+            limited = true;
             break;
         }
+
+        let commit_oid = commit_oid?;
+        if config.include_reflog {
+            walked_oids.insert(commit_oid);
+        }
+        let commit = repo.find_commit(commit_oid)?;
+        collector.add(&commit, &commit.author(), commit.time())?;
+    }
+
+    if config.include_reflog && !limited {
+        limited = add_reflog_commits(repo, walked_oids, &mut collector)?;
+    }
+
+    collector.finish(limited)
+}
+
+/// Commits that refs, including `HEAD`, point at
+fn ref_tips(repo: &Repository) -> Result<Vec<git2::Oid>, git2::Error> {
+    let mut tips: Vec<git2::Oid> = repo
+        .references()?
+        .filter_map(|reference| reference.ok()?.peel_to_commit().ok())
+        .map(|commit| commit.id())
+        .collect();
+    if let Ok(head) = repo.head().and_then(|head| head.peel_to_commit()) {
+        tips.push(head.id());
+    }
+    Ok(tips)
+}
+
+/// Count commits recorded in the local HEAD reflog that no ref reaches any more, such as amended
+/// or reset-away commits, through the same filters as history. Each is credited to the entry's
+/// committer at the entry's time, i.e. whoever made the commit in this clone. Returns whether
+/// `max_commits` stopped it before every entry was seen.
+fn add_reflog_commits(
+    repo: &Repository,
+    mut seen_oids: HashSet<git2::Oid>,
+    collector: &mut Collector,
+) -> Result<bool, RepositoryError> {
+    let tips = ref_tips(repo)?;
+    let reflog = repo.reflog("HEAD")?;
+    let kept_from_history = collector.commits_kept;
+
+    for entry in reflog.iter() {
+        // Only entries written by committing; checkouts and resets don't represent new work
+        if !entry.message().is_some_and(|m| m.starts_with("commit")) {
+            continue;
+        }
+        let oid = entry.id_new();
+        if !seen_oids.insert(oid) {
+            continue;
+        }
+        let reachable = tips
+            .iter()
+            .any(|&tip| tip == oid || repo.graph_descendant_of(tip, oid).unwrap_or(false));
+        if reachable {
+            continue;
+        }
+
+        if collector.is_full() {
+            return Ok(true);
+        }
+        // The commit object may already have been pruned
+        let Ok(commit) = repo.find_commit(oid) else {
+            continue;
+        };
+        let committer = entry.committer();
+        collector.add(&commit, &committer, committer.when())?;
+    }
+
+    debug!(
+        "Counted {} more commits from the HEAD reflog",
+        collector.commits_kept - kept_from_history
+    );
+    Ok(false)
+}
+
+/// Count annotated tags by their tagger; lightweight tags have no tagger and are skipped
+fn tags_by_tagger(
+    repo: &Repository,
+    config: &RepositoryConfig,
+) -> Result<HashMap<String, usize>, RepositoryError> {
+    let mut tags_by_tagger: HashMap<String, usize> = HashMap::new();
+
+    for reference in repo.references_glob("refs/tags/*")? {
+        let Ok(tag) = reference?.peel_to_tag() else {
+            continue;
+        };
+        let Some(tagger) = tag.tagger() else {
+            continue;
+        };
+        let Some(email) = tagger.email() else {
+            continue;
+        };
+
+        let email = author_key(config, email, tagger.name().unwrap_or_default());
+        *tags_by_tagger.entry(email).or_insert(0) += 1;
+    }
+
+    Ok(tags_by_tagger)
+}
+
+/// Open the repository at `repo_path`, or locate it like git does (honoring `GIT_DIR` and
+/// `GIT_WORK_TREE`) when no path is given
+fn open_repository(repo_path: Option<&str>) -> Result<Repository, RepositoryError> {
+    let repo = match repo_path {
+        Some(repo_path) => Repository::open(Path::new(repo_path)),
+        None => Repository::open_from_env(),
+    };
+
+    repo.map_err(|source| RepositoryError::NotFound {
+        path: repo_path.unwrap_or(".").to_owned(),
+        source,
+    })
+}
+
+/// Number of uncommitted changes in the working tree, or `None` for bare repositories
+fn uncommitted_changes(repo: &Repository) -> Option<usize> {
+    if repo.is_bare() {
+        return None;
+    }
+
+    let mut options = StatusOptions::new();
+    options.include_untracked(true).include_ignored(false);
+    repo.statuses(Some(&mut options))
+        .ok()
+        .map(|statuses| statuses.len())
+}
+
+fn single_author_note(author_count: usize) -> Option<&'static str> {
+    (author_count == 1)
+        .then_some("Only one author was found; if more were expected, check the filtering options")
+}
+
+/// Delay before the first retry, doubled after every further attempt up to `MAX_RETRY_DELAY`
+const RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Longest wait between two attempts, so many retries don't add up to hours
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+fn next_retry_delay(delay: Duration) -> Duration {
+    delay.saturating_mul(2).min(MAX_RETRY_DELAY)
+}
+
+/// Run `operation`, retrying up to `retries` more times with backoff while it fails transiently
+fn with_retries<T>(
+    retries: u32,
+    delay: Duration,
+    mut operation: impl FnMut() -> Result<T, RepositoryError>,
+) -> Result<T, RepositoryError> {
+    let mut delay = delay;
+    let mut attempt = 0;
+
+    loop {
+        match operation() {
+            Err(e) if e.is_transient() && attempt < retries => {
+                attempt += 1;
+                thread::sleep(delay);
+                delay = next_retry_delay(delay);
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Collection time above which a missing commit-graph is pointed out as a note rather than at debug level
+const SLOW_COLLECTION: Duration = Duration::from_secs(10);
+
+/// Whether git has written a commit-graph file, which libgit2 uses to speed up revwalks
+fn has_commit_graph(repo: &Repository) -> bool {
+    let info = repo.path().join("objects").join("info");
+    info.join("commit-graph").is_file() || info.join("commit-graphs").is_dir()
+}
+
+/// Whether a commit-graph is present, logged at debug level, or a suggestion to write one that
+/// becomes a note when collection was slow
+fn commit_graph_note(present: bool, collection: Duration) -> (Level, &'static str) {
+    if present {
+        (
+            Level::Debug,
+            "A commit-graph file is present and used to speed up traversal",
+        )
+    } else {
+        let level = if collection > SLOW_COLLECTION {
+            Level::Info
+        } else {
+            Level::Debug
+        };
+        (
+            level,
+            "No commit-graph file found; `git commit-graph write --reachable` can speed up traversal",
+        )
+    }
+}
+
+/// Log the collection, reporting and total times, visible with `--verbose` or `RUST_LOG=debug`
+fn log_timings(collection: Duration, reporting: Duration) {
+    debug!("Collection took {:.2?}", collection);
+    debug!("Reporting took {:.2?}", reporting);
+    debug!("Total time {:.2?}", collection + reporting);
+}
+
+pub fn get_status(
+    repo_path: Option<&str>,
+    config: &RepositoryConfig,
+    mut table_options: TableOptions,
+) -> Result<(), RepositoryError> {
+    let started = Instant::now();
+    let result = with_retries(config.retries, RETRY_DELAY, || {
+        let repo = open_repository(repo_path)?;
+        let commit_stats = collect_commit_info(&repo, config)?;
+        Ok((repo, commit_stats))
+    });
+
+    let (repo, commit_stats) = result?;
+    let collection = started.elapsed();
+
+    let (level, note) = commit_graph_note(has_commit_graph(&repo), collection);
+    log!(level, "{}", note);
+
+    match config.scope() {
+        Ok(Scope::Head) | Err(_) => {}
+        Ok(scope) => info!("Counting {}", scope),
+    }
+
+    if let Some(note) = single_author_note(commit_stats.authors.len()) {
+        info!("{}", note);
+    }
+
+    if !config.skip_status {
+        match uncommitted_changes(&repo) {
+            Some(0) | None => {}
+            Some(changes) => info!(
+                "{} uncommitted changes present; only committed history is counted",
+                changes
+            ),
+        }
+    }
+
+    if config.mark_me {
+        match current_user(&repo, config) {
+            Some(user) if commit_stats.authors.iter().any(|(email, _)| *email == user) => {
+                table_options.current_user = Some(user);
+            }
+            Some(user) => info!("No commits by {} were counted", user),
+            None => info!("No user.email is configured for this repository"),
+        }
     }
+
+    if let Some(team) = table_options.team.take() {
+        let members = team_members(config, &team, &commit_stats.authors);
+        info!(
+            "{} of {} authors are team members (marked with +)",
+            members.len(),
+            commit_stats.authors.len()
+        );
+        table_options.team = Some(members);
+    }
+
+    if config.alert_low.is_some() || config.alert_high.is_some() {
+        if let Some(alert) = check_velocity(
+            &commit_stats.authors,
+            table_options.today,
+            config.alert_low,
+            config.alert_high,
+        ) {
+            warn!("{}", alert.message());
+        }
+    }
+
+    if commit_stats.limited {
+        if let Some(max_commits) = config.max_commits {
+            warn!(
+                "Results limited to {} commits; raise --max-commits to see more",
+                max_commits
+            );
+        }
+    }
+
+    print_commits(commit_stats.authors, &table_options).map_err(RepositoryError::Output)?;
+
+    if let Some(limit) = config.top_words {
+        print_top_words(&commit_stats.word_counts, limit);
+    }
+
+    if config.timezones {
+        print_timezones(&commit_stats.timezone_counts);
+    }
+
+    if config.by_extension {
+        print_extensions(&commit_stats.commits_by_extension);
+    }
+
+    if let Some(path) = &config.export_matrix {
+        export_matrix(path, &commit_stats.commits_by_day).map_err(|source| {
+            RepositoryError::Export {
+                path: path.clone(),
+                source,
+            }
+        })?;
+    }
+
+    let reporting = started.elapsed() - collection;
+    log_timings(collection, reporting);
+    Ok(())
 }
 
-pub fn get_status(repo_path: &str) {
-    let repo: Repository = match Repository::open(Path::new(repo_path)) {
-        Ok(repo) => repo,
-        Err(e) => {
-            eprintln!(
-                "Error: Could not open the Git repository at '{}'.\nDetails: {}",
-                repo_path, e
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::{Oid, Signature, Time};
+    use std::collections::BTreeMap;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Commit `files` on top of `reference` (creating it if needed) as `email` at `seconds`
+    fn commit(
+        repo: &Repository,
+        reference: &str,
+        email: &str,
+        seconds: i64,
+        files: &[(&str, &str)],
+    ) -> Oid {
+        commit_with_offset(repo, reference, email, seconds, 0, files)
+    }
+
+    fn commit_with_offset(
+        repo: &Repository,
+        reference: &str,
+        email: &str,
+        seconds: i64,
+        offset_minutes: i32,
+        files: &[(&str, &str)],
+    ) -> Oid {
+        let signature = Signature::new(email, email, &Time::new(seconds, offset_minutes)).unwrap();
+        commit_as(repo, reference, &signature, "Test commit", files)
+    }
+
+    fn commit_as(
+        repo: &Repository,
+        reference: &str,
+        signature: &Signature,
+        message: &str,
+        files: &[(&str, &str)],
+    ) -> Oid {
+        let parent = repo
+            .find_reference(reference)
+            .ok()
+            .and_then(|r| r.peel_to_commit().ok());
+
+        let mut builder = repo
+            .treebuilder(parent.as_ref().map(|p| p.tree().unwrap()).as_ref())
+            .unwrap();
+        for (path, contents) in files {
+            let blob = repo.blob(contents.as_bytes()).unwrap();
+            builder.insert(path, blob, 0o100644).unwrap();
+        }
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(
+            Some(reference),
+            signature,
+            signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap()
+    }
+
+    fn init_repo() -> (TempDir, Repository) {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        (dir, repo)
+    }
+
+    fn total_commits(stats: &CommitStats) -> u32 {
+        stats.authors.iter().map(|(_, info)| info.commits).sum()
+    }
+
+    fn commit_counts(stats: &CommitStats) -> HashMap<&str, u32> {
+        stats
+            .authors
+            .iter()
+            .map(|(email, info)| (email.as_str(), info.commits))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("main..feature").unwrap(), ("main", "feature"));
+        assert!(parse_range("main").is_err());
+        assert!(parse_range("..feature").is_err());
+        assert!(parse_range("main...feature").is_err());
+    }
+
+    #[test]
+    fn test_range_counts_only_branch_commits() {
+        let (_dir, repo) = init_repo();
+        commit(
+            &repo,
+            "refs/heads/main",
+            "alice@example.com",
+            1_000_000,
+            &[("a", "1")],
+        );
+        commit(
+            &repo,
+            "refs/heads/main",
+            "alice@example.com",
+            1_100_000,
+            &[("a", "2")],
+        );
+        repo.branch(
+            "feature",
+            &repo.head().unwrap().peel_to_commit().unwrap(),
+            false,
+        )
+        .unwrap();
+        commit(
+            &repo,
+            "refs/heads/feature",
+            "bob@example.com",
+            1_200_000,
+            &[("b", "1")],
+        );
+        commit(
+            &repo,
+            "refs/heads/feature",
+            "bob@example.com",
+            1_300_000,
+            &[("b", "2")],
+        );
+
+        let config = RepositoryConfig {
+            range: Some("main..feature".to_string()),
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        assert_eq!(
+            commit_counts(&stats),
+            HashMap::from([("bob@example.com", 2)])
+        );
+
+        let config = RepositoryConfig {
+            range: Some("main..missing".to_string()),
+            ..Default::default()
+        };
+        assert!(collect_commit_info(&repo, &config).is_err());
+    }
+
+    #[test]
+    fn test_max_scan_bounds_walked_commits() {
+        let (_dir, repo) = init_repo();
+        // 2001, 2002, 2020 and 2021; the walk visits them newest first
+        for seconds in [978_307_200, 1_009_843_200, 1_577_836_800, 1_609_459_200] {
+            commit(&repo, "refs/heads/main", "alice@example.com", seconds, &[]);
+        }
+
+        let collect = |max_commits, max_scan| {
+            let config = RepositoryConfig {
+                until: NaiveDate::from_ymd_opt(2010, 1, 1),
+                max_commits,
+                max_scan,
+                ..Default::default()
+            };
+            total_commits(&collect_commit_info(&repo, &config).unwrap())
+        };
+
+        assert_eq!(collect(None, None), 2);
+        assert_eq!(collect(Some(1), None), 1);
+        assert_eq!(collect(Some(2), None), 2);
+        assert_eq!(collect(None, Some(3)), 1);
+        assert_eq!(collect(None, Some(2)), 0);
+        assert_eq!(collect(Some(5), Some(3)), 1);
+    }
+
+    #[test]
+    fn test_timezone_buckets() {
+        let (_dir, repo) = init_repo();
+        for offset_minutes in [120, 120, -300, 0] {
+            let author = "alice@example.com";
+            commit_with_offset(
+                &repo,
+                "refs/heads/main",
+                author,
+                1_000_000,
+                offset_minutes,
+                &[],
             );
+        }
+
+        let config = RepositoryConfig {
+            timezones: true,
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        assert_eq!(
+            stats.timezone_counts,
+            HashMap::from([(120, 2), (-300, 1), (0, 1)])
+        );
+    }
+
+    #[test]
+    fn test_follow_counts_only_commits_touching_path() {
+        let (_dir, repo) = init_repo();
+        commit(
+            &repo,
+            "refs/heads/main",
+            "alice@example.com",
+            1_000_000,
+            &[("README", "1")],
+        );
+        commit(
+            &repo,
+            "refs/heads/main",
+            "bob@example.com",
+            1_100_000,
+            &[("lib.rs", "1")],
+        );
+        commit(
+            &repo,
+            "refs/heads/main",
+            "alice@example.com",
+            1_200_000,
+            &[("README", "2")],
+        );
+        commit(
+            &repo,
+            "refs/heads/main",
+            "bob@example.com",
+            1_300_000,
+            &[("lib.rs", "2")],
+        );
+
+        let config = RepositoryConfig {
+            follow: Some("lib.rs".to_string()),
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        assert_eq!(
+            commit_counts(&stats),
+            HashMap::from([("bob@example.com", 2)])
+        );
+    }
+
+    #[test]
+    fn test_open_repository_from_git_dir() {
+        let (dir, _repo) = init_repo();
+
+        // Setting GIT_DIR in this process would race with the other tests, so run the check in a
+        // child test process that has it set
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "--exact",
+                "repository::tests::open_repository_honors_git_dir",
+                "--ignored",
+            ])
+            .env("GIT_DIR", dir.path().join(".git"))
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(output.status.success(), "{}", stdout);
+        assert!(stdout.contains("1 passed"), "{}", stdout);
+    }
+
+    #[test]
+    #[ignore = "run by test_open_repository_from_git_dir with GIT_DIR set"]
+    fn open_repository_honors_git_dir() {
+        let Some(git_dir) = std::env::var_os("GIT_DIR") else {
             return;
+        };
+
+        let repo = open_repository(None).unwrap();
+        assert_eq!(
+            repo.path().canonicalize().unwrap(),
+            Path::new(&git_dir).canonicalize().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_skip_root() {
+        let (_dir, repo) = init_repo();
+        commit(
+            &repo,
+            "refs/heads/main",
+            "importer@example.com",
+            1_000_000,
+            &[],
+        );
+        commit(
+            &repo,
+            "refs/heads/main",
+            "alice@example.com",
+            1_100_000,
+            &[],
+        );
+
+        let stats = collect_commit_info(&repo, &RepositoryConfig::default()).unwrap();
+        assert_eq!(
+            commit_counts(&stats),
+            HashMap::from([("importer@example.com", 1), ("alice@example.com", 1)])
+        );
+
+        let config = RepositoryConfig {
+            skip_root: true,
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        assert_eq!(
+            commit_counts(&stats),
+            HashMap::from([("alice@example.com", 1)])
+        );
+    }
+
+    #[test]
+    fn test_files_touched() {
+        let (_dir, repo) = init_repo();
+        let alice = "alice@example.com";
+        commit(
+            &repo,
+            "refs/heads/main",
+            alice,
+            1_000_000,
+            &[("a", "1"), ("b", "1")],
+        );
+        commit(
+            &repo,
+            "refs/heads/main",
+            "bob@example.com",
+            1_100_000,
+            &[("b", "2")],
+        );
+        commit(
+            &repo,
+            "refs/heads/main",
+            alice,
+            1_200_000,
+            &[("a", "2"), ("c", "1")],
+        );
+
+        let config = RepositoryConfig {
+            files_touched: true,
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        let files_touched: HashMap<&str, usize> = stats
+            .authors
+            .iter()
+            .map(|(email, info)| (email.as_str(), info.files_touched))
+            .collect();
+        assert_eq!(
+            files_touched,
+            HashMap::from([(alice, 3), ("bob@example.com", 1)])
+        );
+    }
+
+    #[test]
+    fn test_aliases() {
+        let (_dir, repo) = init_repo();
+        let email = "alice@example.com";
+        for (name, seconds) in [
+            ("Alice", 1_000_000),
+            ("alice", 1_100_000),
+            ("Alice", 1_200_000),
+        ] {
+            let signature = Signature::new(name, email, &Time::new(seconds, 0)).unwrap();
+            commit_as(&repo, "refs/heads/main", &signature, "Test commit", &[]);
         }
-    };
 
-    let commit_info_vec: Vec<(String, UserCommitInfo)> = collect_commit_info(repo);
+        let config = RepositoryConfig {
+            aliases: true,
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        assert_eq!(stats.authors.len(), 1);
+        assert_eq!(stats.authors[0].1.aliases, 2);
+    }
+
+    #[test]
+    fn test_commit_days_only_when_requested() {
+        let (_dir, repo) = init_repo();
+        let day = 86_400;
+        for seconds in [0, 60, 3 * day] {
+            commit(&repo, "refs/heads/main", "alice@example.com", seconds, &[]);
+        }
+
+        let stats = collect_commit_info(&repo, &RepositoryConfig::default()).unwrap();
+        assert!(stats.authors[0].1.commit_days.is_empty());
+
+        let config = RepositoryConfig {
+            commit_days: true,
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        assert_eq!(stats.authors[0].1.commit_days.len(), 2);
+        assert_eq!(stats.authors[0].1.longest_gap(), 3);
+    }
+
+    #[test]
+    fn test_monthly_counts_only_when_requested() {
+        let (_dir, repo) = init_repo();
+        let day = 86_400;
+        for seconds in [0, day, 40 * day] {
+            commit(&repo, "refs/heads/main", "alice@example.com", seconds, &[]);
+        }
+
+        let stats = collect_commit_info(&repo, &RepositoryConfig::default()).unwrap();
+        assert!(stats.authors[0].1.commits_by_month.is_empty());
+
+        let config = RepositoryConfig {
+            monthly_counts: true,
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        assert_eq!(
+            stats.authors[0].1.commits_by_month,
+            BTreeMap::from([((1970, 1), 2), ((1970, 2), 1)])
+        );
+    }
+
+    #[test]
+    fn test_weight_ext_counts_only_matching_lines() {
+        let (_dir, repo) = init_repo();
+        let alice = "alice@example.com";
+        commit(
+            &repo,
+            "refs/heads/main",
+            alice,
+            1_000_000,
+            &[("lib.rs", "fn a() {}\n"), ("README.md", "# Title\n")],
+        );
+        commit(
+            &repo,
+            "refs/heads/main",
+            alice,
+            1_100_000,
+            &[
+                ("lib.rs", "fn b() {}\nfn c() {}\n"),
+                ("README.md", "# Title\n\nMore\nText\n"),
+            ],
+        );
+
+        let config = RepositoryConfig {
+            weight_ext: Some(".rs".to_string()),
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        // One added line, then one replaced and one added
+        assert_eq!(stats.authors[0].1.weighted_lines, 4);
+    }
+
+    #[test]
+    fn test_first_n_days() {
+        let (_dir, repo) = init_repo();
+        let day = 86_400;
+        commit(
+            &repo,
+            "refs/heads/main",
+            "alice@example.com",
+            1_000 * day,
+            &[],
+        );
+        commit(
+            &repo,
+            "refs/heads/main",
+            "bob@example.com",
+            1_005 * day,
+            &[],
+        );
+        commit(
+            &repo,
+            "refs/heads/main",
+            "carol@example.com",
+            1_011 * day,
+            &[],
+        );
+
+        let config = RepositoryConfig {
+            first_n_days: Some(10),
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        assert_eq!(
+            commit_counts(&stats),
+            HashMap::from([("alice@example.com", 1), ("bob@example.com", 1)])
+        );
+    }
+
+    #[test]
+    fn test_first_n_days_overflow_is_an_error() {
+        let (_dir, repo) = init_repo();
+        commit(
+            &repo,
+            "refs/heads/main",
+            "alice@example.com",
+            1_000_000,
+            &[],
+        );
+
+        let config = RepositoryConfig {
+            first_n_days: Some(u32::MAX),
+            ..Default::default()
+        };
+        assert!(matches!(
+            collect_commit_info(&repo, &config),
+            Err(RepositoryError::FirstDaysOutOfRange(u32::MAX))
+        ));
+    }
+
+    #[test]
+    fn test_normalize_github_noreply_merges_variants() {
+        let (_dir, repo) = init_repo();
+        for email in [
+            "12345+octocat@users.noreply.github.com",
+            "octocat@users.noreply.github.com",
+        ] {
+            commit(&repo, "refs/heads/main", email, 1_000_000, &[]);
+        }
+
+        let config = RepositoryConfig {
+            normalize_github_noreply: true,
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        assert_eq!(commit_counts(&stats), HashMap::from([("octocat", 2)]));
+    }
+
+    #[test]
+    fn test_single_author_note() {
+        assert!(single_author_note(1).is_some());
+        assert!(single_author_note(0).is_none());
+        assert!(single_author_note(2).is_none());
+    }
+
+    #[test]
+    fn test_validate_date_range() {
+        let config = |since, until| RepositoryConfig {
+            since: NaiveDate::from_ymd_opt(2024, since, 1),
+            until: NaiveDate::from_ymd_opt(2024, until, 1),
+            ..Default::default()
+        };
+
+        assert!(config(1, 6).validate().is_ok());
+        assert!(config(6, 6).validate().is_ok());
+        assert!(config(6, 1).validate().is_err());
+        assert!(RepositoryConfig::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_split_by_name() {
+        let (_dir, repo) = init_repo();
+        let email = "bot@example.com";
+        for name in ["Deploy Bot", "Release Bot", "Deploy Bot"] {
+            let signature = Signature::new(name, email, &Time::new(1_000_000, 0)).unwrap();
+            commit_as(&repo, "refs/heads/main", &signature, "Test commit", &[]);
+        }
+
+        let stats = collect_commit_info(&repo, &RepositoryConfig::default()).unwrap();
+        assert_eq!(commit_counts(&stats), HashMap::from([(email, 3)]));
+
+        let config = RepositoryConfig {
+            split_by_name: true,
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        assert_eq!(
+            commit_counts(&stats),
+            HashMap::from([
+                ("Deploy Bot <bot@example.com>", 2),
+                ("Release Bot <bot@example.com>", 1)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_limited_when_max_commits_is_hit() {
+        let (_dir, repo) = init_repo();
+        for seconds in [1_000_000, 1_100_000, 1_200_000] {
+            commit(&repo, "refs/heads/main", "alice@example.com", seconds, &[]);
+        }
+
+        let limited = |max_commits| {
+            let config = RepositoryConfig {
+                max_commits,
+                ..Default::default()
+            };
+            collect_commit_info(&repo, &config).unwrap().limited
+        };
+
+        assert!(limited(Some(2)));
+        assert!(!limited(Some(3)));
+        assert!(!limited(None));
+    }
+
+    #[test]
+    fn test_uncommitted_changes() {
+        let (dir, repo) = init_repo();
+        commit(
+            &repo,
+            "refs/heads/main",
+            "alice@example.com",
+            1_000_000,
+            &[],
+        );
+        assert_eq!(uncommitted_changes(&repo), Some(0));
+
+        std::fs::write(dir.path().join("notes.txt"), "work in progress").unwrap();
+        assert_eq!(uncommitted_changes(&repo), Some(1));
+    }
+
+    #[test]
+    fn test_not_found_error() {
+        let dir = TempDir::new().unwrap();
+        let missing = dir.path().join("missing");
 
-    print_commits(commit_info_vec);
+        let result = open_repository(missing.to_str());
+        assert!(matches!(result, Err(RepositoryError::NotFound { .. })));
+    }
+
+    #[test]
+    fn test_empty_repository_error() {
+        let (_dir, repo) = init_repo();
+
+        let result = collect_commit_info(&repo, &RepositoryConfig::default());
+        assert!(matches!(result, Err(RepositoryError::EmptyRepository)));
+    }
+
+    #[test]
+    fn test_retry_delay_is_capped() {
+        assert_eq!(next_retry_delay(RETRY_DELAY), Duration::from_millis(200));
+        assert_eq!(
+            next_retry_delay(Duration::from_millis(1500)),
+            MAX_RETRY_DELAY
+        );
+        assert_eq!(next_retry_delay(Duration::MAX), MAX_RETRY_DELAY);
+
+        let mut delay = RETRY_DELAY;
+        for _ in 0..100 {
+            delay = next_retry_delay(delay);
+        }
+        assert_eq!(delay, MAX_RETRY_DELAY);
+    }
+
+    #[test]
+    fn test_with_retries() {
+        let locked = || {
+            RepositoryError::Git(git2::Error::new(
+                ErrorCode::Locked,
+                git2::ErrorClass::Index,
+                "index is locked",
+            ))
+        };
+
+        let mut attempts = 0;
+        let result = with_retries(2, Duration::ZERO, || {
+            attempts += 1;
+            if attempts < 3 {
+                Err(locked())
+            } else {
+                Ok(attempts)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+
+        let mut attempts = 0;
+        let result: Result<(), _> = with_retries(1, Duration::ZERO, || {
+            attempts += 1;
+            Err(locked())
+        });
+        assert!(result.is_err());
+        assert_eq!(attempts, 2);
+
+        let mut attempts = 0;
+        let result: Result<(), _> = with_retries(3, Duration::ZERO, || {
+            attempts += 1;
+            Err(RepositoryError::EmptyRepository)
+        });
+        assert!(matches!(result, Err(RepositoryError::EmptyRepository)));
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn test_scope() {
+        let config = |head: Option<&str>, range: Option<&str>| RepositoryConfig {
+            head: head.map(str::to_owned),
+            range: range.map(str::to_owned),
+            ..Default::default()
+        };
+
+        assert_eq!(config(None, None).scope().unwrap(), Scope::Head);
+        assert_eq!(
+            config(Some("v1.0"), None).scope().unwrap(),
+            Scope::Revision("v1.0")
+        );
+        assert_eq!(
+            config(None, Some("main..feature")).scope().unwrap(),
+            Scope::Range("main", "feature")
+        );
+        assert!(matches!(
+            config(Some("v1.0"), Some("main..feature")).scope(),
+            Err(RepositoryError::ConflictingOptions(..))
+        ));
+    }
+
+    #[test]
+    fn test_head_walks_from_revision() {
+        let (_dir, repo) = init_repo();
+        let first = commit(
+            &repo,
+            "refs/heads/main",
+            "alice@example.com",
+            1_000_000,
+            &[],
+        );
+        commit(&repo, "refs/heads/main", "bob@example.com", 1_100_000, &[]);
+
+        let config = RepositoryConfig {
+            head: Some(first.to_string()),
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        assert_eq!(
+            commit_counts(&stats),
+            HashMap::from([("alice@example.com", 1)])
+        );
+    }
+
+    #[test]
+    fn test_head_peels_annotated_tag() {
+        let (_dir, repo) = init_repo();
+        let first = commit(
+            &repo,
+            "refs/heads/main",
+            "alice@example.com",
+            1_000_000,
+            &[],
+        );
+        commit(&repo, "refs/heads/main", "bob@example.com", 1_100_000, &[]);
+
+        let tagger =
+            Signature::new("Alice", "alice@example.com", &Time::new(1_000_100, 0)).unwrap();
+        let target = repo.find_object(first, None).unwrap();
+        repo.tag("v1.0", &target, &tagger, "Release 1.0", false)
+            .unwrap();
+
+        let config = RepositoryConfig {
+            head: Some("v1.0".to_owned()),
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        assert_eq!(
+            commit_counts(&stats),
+            HashMap::from([("alice@example.com", 1)])
+        );
+
+        let config = RepositoryConfig {
+            range: Some("v1.0..main".to_owned()),
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        assert_eq!(
+            commit_counts(&stats),
+            HashMap::from([("bob@example.com", 1)])
+        );
+    }
+
+    #[test]
+    fn test_group_by_domain() {
+        let (_dir, repo) = init_repo();
+        commit(
+            &repo,
+            "refs/heads/main",
+            "alice@example.com",
+            1_000_000,
+            &[],
+        );
+        commit(&repo, "refs/heads/main", "bob@Example.com", 1_100_000, &[]);
+        commit(&repo, "refs/heads/main", "carol@other.org", 1_200_000, &[]);
+
+        let config = RepositoryConfig {
+            group_by: GroupBy::Domain,
+            export_matrix: Some(String::new()),
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        assert_eq!(
+            commit_counts(&stats),
+            HashMap::from([("example.com", 2), ("other.org", 1)])
+        );
+
+        let exported: u32 = stats.commits_by_day["example.com"].values().sum();
+        assert_eq!(exported, 2);
+    }
+
+    #[test]
+    fn test_current_user_matches_author_key() {
+        let (_dir, repo) = init_repo();
+        let mut git_config = repo.config().unwrap();
+        git_config
+            .set_str("user.email", "12345+alice@users.noreply.github.com")
+            .unwrap();
+        git_config.set_str("user.name", "Alice").unwrap();
+
+        let config = RepositoryConfig::default();
+        assert_eq!(
+            current_user(&repo, &config).as_deref(),
+            Some("12345+alice@users.noreply.github.com")
+        );
+
+        let config = RepositoryConfig {
+            normalize_github_noreply: true,
+            split_by_name: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            current_user(&repo, &config).as_deref(),
+            Some("Alice <alice>")
+        );
+    }
+
+    #[test]
+    fn test_team_members_match_however_authors_are_keyed() {
+        let (_dir, repo) = init_repo();
+        for (name, email) in [
+            ("Alice", "alice@example.com"),
+            ("Al", "alice@example.com"),
+            ("Bob", "bob@example.com"),
+            ("Carol", "carol@other.org"),
+        ] {
+            let signature = Signature::new(name, email, &Time::new(1_000_000, 0)).unwrap();
+            commit_as(&repo, "refs/heads/main", &signature, "Test commit", &[]);
+        }
+        let team = Team::from_emails(["Alice@Example.com".to_string()]);
+        let members = |config: &RepositoryConfig| {
+            let stats = collect_commit_info(&repo, config).unwrap();
+            let members = team_members(config, &team, &stats.authors);
+            let mut keys: Vec<String> = stats
+                .authors
+                .into_iter()
+                .map(|(key, _)| key)
+                .filter(|key| members.contains(key))
+                .collect();
+            keys.sort();
+            keys
+        };
+
+        assert_eq!(members(&RepositoryConfig::default()), ["alice@example.com"]);
+
+        let config = RepositoryConfig {
+            split_by_name: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            members(&config),
+            ["Al <alice@example.com>", "Alice <alice@example.com>"]
+        );
+
+        let config = RepositoryConfig {
+            group_by: GroupBy::Domain,
+            ..Default::default()
+        };
+        assert_eq!(members(&config), ["example.com"]);
+    }
+
+    #[test]
+    fn test_tags_credit_tagger() {
+        let (_dir, repo) = init_repo();
+        let first = commit(
+            &repo,
+            "refs/heads/main",
+            "alice@example.com",
+            1_000_000,
+            &[],
+        );
+        commit(&repo, "refs/heads/main", "bob@example.com", 1_100_000, &[]);
+
+        let target = repo.find_object(first, None).unwrap();
+        let tagger = Signature::new("Bob", "bob@example.com", &Time::new(1_200_000, 0)).unwrap();
+        repo.tag("v1.0", &target, &tagger, "Release 1.0", false)
+            .unwrap();
+        repo.tag_lightweight("nightly", &target, false).unwrap();
+
+        let config = RepositoryConfig {
+            tags: true,
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        let tags: HashMap<&str, usize> = stats
+            .authors
+            .iter()
+            .map(|(email, info)| (email.as_str(), info.tags))
+            .collect();
+        assert_eq!(
+            tags,
+            HashMap::from([("alice@example.com", 0), ("bob@example.com", 1)])
+        );
+    }
+
+    #[test]
+    fn test_commit_graph_note() {
+        let fast = Duration::from_millis(50);
+        let slow = SLOW_COLLECTION * 2;
+
+        let (level, note) = commit_graph_note(true, slow);
+        assert_eq!(level, Level::Debug);
+        assert!(note.contains("is present"));
+
+        let (level, note) = commit_graph_note(false, fast);
+        assert_eq!(level, Level::Debug);
+        assert!(note.contains("git commit-graph write"));
+        assert_eq!(commit_graph_note(false, slow).0, Level::Info);
+
+        let (_dir, repo) = init_repo();
+        assert!(!has_commit_graph(&repo));
+        fs::write(repo.path().join("objects/info/commit-graph"), "").unwrap();
+        assert!(has_commit_graph(&repo));
+    }
+
+    /// Keeps every log record so tests can inspect what was logged
+    struct CapturingLogger;
+
+    static CAPTURED: std::sync::Mutex<Vec<(log::Level, String)>> =
+        std::sync::Mutex::new(Vec::new());
+
+    impl log::Log for CapturingLogger {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            CAPTURED
+                .lock()
+                .unwrap()
+                .push((record.level(), record.args().to_string()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    /// Route log records to `CAPTURED`; every test shares the one logger
+    fn capture_logs() {
+        static LOGGER: CapturingLogger = CapturingLogger;
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(log::LevelFilter::Trace);
+    }
+
+    fn was_logged(level: Level, message: &str) -> bool {
+        CAPTURED
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|(l, m)| *l == level && m == message)
+    }
+
+    #[test]
+    fn test_skipped_commit_logs_warning() {
+        capture_logs();
+
+        // A timestamp past chrono's range, which git accepts but can't be given a date
+        let (_dir, repo) = init_repo();
+        let tree = repo.treebuilder(None).unwrap().write().unwrap();
+        let raw = format!(
+            "tree {}\n\
+             author Alice <alice@example.com> 99999999999999 +0000\n\
+             committer Alice <alice@example.com> 99999999999999 +0000\n\n\
+             From the far future\n",
+            tree
+        );
+        let oid = repo
+            .odb()
+            .unwrap()
+            .write(git2::ObjectType::Commit, raw.as_bytes())
+            .unwrap();
+        repo.reference("refs/heads/main", oid, false, "far future")
+            .unwrap();
+
+        let stats = collect_commit_info(&repo, &RepositoryConfig::default()).unwrap();
+        assert!(stats.authors.is_empty());
+        assert!(was_logged(
+            Level::Warn,
+            &format!("Skipping commit {}: the commit time is out of range", oid)
+        ));
+    }
+
+    #[test]
+    fn test_timings_are_logged_at_debug() {
+        capture_logs();
+
+        log_timings(Duration::from_millis(1500), Duration::from_millis(250));
+        assert!(was_logged(Level::Debug, "Collection took 1.50s"));
+        assert!(was_logged(Level::Debug, "Reporting took 250.00ms"));
+        assert!(was_logged(Level::Debug, "Total time 1.75s"));
+    }
+
+    #[test]
+    fn test_ignore_reverts() {
+        let (_dir, repo) = init_repo();
+        let alice = Signature::new("Alice", "alice@example.com", &Time::new(1_000_000, 0)).unwrap();
+        let bob = Signature::new("Bob", "bob@example.com", &Time::new(1_100_000, 0)).unwrap();
+        let original = commit_as(&repo, "refs/heads/main", &alice, "Add feature", &[]);
+        commit_as(
+            &repo,
+            "refs/heads/main",
+            &bob,
+            "Revert \"Add feature\"",
+            &[],
+        );
+        commit_as(
+            &repo,
+            "refs/heads/main",
+            &bob,
+            &format!("Back out the feature\n\nThis reverts commit {}.", original),
+            &[],
+        );
+        commit_as(&repo, "refs/heads/main", &bob, "Fix revert handling", &[]);
+
+        let stats = collect_commit_info(&repo, &RepositoryConfig::default()).unwrap();
+        assert_eq!(total_commits(&stats), 4);
+
+        let config = RepositoryConfig {
+            ignore_reverts: true,
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        assert_eq!(
+            commit_counts(&stats),
+            HashMap::from([("alice@example.com", 1), ("bob@example.com", 1)])
+        );
+    }
+
+    #[test]
+    fn test_whitespace_padded_email_merges() {
+        let (_dir, repo) = init_repo();
+        let alice = Signature::new("Alice", "alice@example.com", &Time::new(1_000_000, 0)).unwrap();
+        let parent = commit_as(&repo, "refs/heads/main", &alice, "Clean identity", &[]);
+        let tree = repo.find_commit(parent).unwrap().tree_id();
+
+        // Written by hand, since `Signature::new` would trim the padding itself
+        let raw = format!(
+            "tree {}\nparent {}\nauthor  Alice  <  alice@example.com\t> 1100000 +0000\n\
+             committer Alice <alice@example.com> 1100000 +0000\n\nPadded identity\n",
+            tree, parent
+        );
+        let padded = repo
+            .odb()
+            .unwrap()
+            .write(git2::ObjectType::Commit, raw.as_bytes())
+            .unwrap();
+        repo.reference("refs/heads/main", padded, true, "padded identity")
+            .unwrap();
+
+        let config = RepositoryConfig {
+            split_by_name: true,
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        assert_eq!(
+            commit_counts(&stats),
+            HashMap::from([("Alice <alice@example.com>", 2)])
+        );
+    }
+
+    #[test]
+    fn test_by_extension() {
+        let (_dir, repo) = init_repo();
+        commit(
+            &repo,
+            "refs/heads/main",
+            "alice@example.com",
+            1_000_000,
+            &[("a.rs", "fn main() {}"), ("b", "data"), ("c.rs", "")],
+        );
+        commit(
+            &repo,
+            "refs/heads/main",
+            "bob@example.com",
+            1_100_000,
+            &[("a.rs", "fn main() { run() }"), ("README.md", "# Readme")],
+        );
+
+        let config = RepositoryConfig {
+            by_extension: true,
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        assert_eq!(
+            stats.commits_by_extension,
+            HashMap::from([
+                (".rs".to_owned(), 2),
+                ("(none)".to_owned(), 1),
+                (".md".to_owned(), 1)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_exclude_weekends() {
+        let (_dir, repo) = init_repo();
+        // Saturday 2023-01-07 and Monday 2023-01-09, both at noon UTC
+        commit(
+            &repo,
+            "refs/heads/main",
+            "alice@example.com",
+            1_673_092_800,
+            &[],
+        );
+        commit(
+            &repo,
+            "refs/heads/main",
+            "bob@example.com",
+            1_673_265_600,
+            &[],
+        );
+
+        let stats = collect_commit_info(&repo, &RepositoryConfig::default()).unwrap();
+        assert_eq!(total_commits(&stats), 2);
+
+        let config = RepositoryConfig {
+            exclude_weekends: true,
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        assert_eq!(
+            commit_counts(&stats),
+            HashMap::from([("bob@example.com", 1)])
+        );
+    }
+
+    #[test]
+    fn test_files_changed_sums_per_commit() {
+        let (_dir, repo) = init_repo();
+        commit(
+            &repo,
+            "refs/heads/main",
+            "alice@example.com",
+            1_000_000,
+            &[("a.rs", "one"), ("b.rs", "two"), ("c.rs", "three")],
+        );
+        commit(
+            &repo,
+            "refs/heads/main",
+            "alice@example.com",
+            1_100_000,
+            &[("a.rs", "one more"), ("d.rs", "four")],
+        );
+        // Rewriting a file with identical contents leaves the tree entry unchanged
+        commit(
+            &repo,
+            "refs/heads/main",
+            "bob@example.com",
+            1_200_000,
+            &[("b.rs", "two")],
+        );
+
+        let config = RepositoryConfig {
+            files_changed: true,
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        let files_changed: HashMap<&str, usize> = stats
+            .authors
+            .iter()
+            .map(|(email, info)| (email.as_str(), info.files_changed))
+            .collect();
+        assert_eq!(
+            files_changed,
+            HashMap::from([("alice@example.com", 5), ("bob@example.com", 0)])
+        );
+    }
+
+    #[test]
+    fn test_include_reflog_counts_amended_commits() {
+        let (_dir, repo) = init_repo();
+        let mut git_config = repo.config().unwrap();
+        git_config.set_str("user.name", "Carol").unwrap();
+        git_config
+            .set_str("user.email", "carol@example.com")
+            .unwrap();
+
+        let alice = Signature::new("Alice", "alice@example.com", &Time::new(1_000_000, 0)).unwrap();
+        let bob = Signature::new("Bob", "bob@example.com", &Time::new(1_100_000, 0)).unwrap();
+        let original = commit_as(&repo, "HEAD", &alice, "First draft", &[]);
+        repo.find_commit(original)
+            .unwrap()
+            .amend(
+                Some("HEAD"),
+                Some(&bob),
+                Some(&bob),
+                None,
+                Some("Final"),
+                None,
+            )
+            .unwrap();
+
+        let stats = collect_commit_info(&repo, &RepositoryConfig::default()).unwrap();
+        assert_eq!(
+            commit_counts(&stats),
+            HashMap::from([("bob@example.com", 1)])
+        );
+
+        let config = RepositoryConfig {
+            include_reflog: true,
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        assert_eq!(
+            commit_counts(&stats),
+            HashMap::from([("alice@example.com", 1), ("bob@example.com", 1)])
+        );
+    }
+
+    #[test]
+    fn test_include_reflog_credits_the_entry_committer() {
+        let (_dir, repo) = init_repo();
+        let alice = Signature::new("Alice", "alice@example.com", &Time::new(1_000_000, 0)).unwrap();
+        let erin = Signature::new("Erin", "erin@example.com", &Time::new(1_100_000, 0)).unwrap();
+        let frank = Signature::new("Frank", "frank@example.com", &Time::new(5_000_000, 0)).unwrap();
+        commit_as(&repo, "HEAD", &alice, "Initial import", &[]);
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        let tree = parent.tree().unwrap();
+        let applied = repo
+            .commit(Some("HEAD"), &erin, &frank, "Patch", &tree, &[&parent])
+            .unwrap();
+        repo.find_commit(applied)
+            .unwrap()
+            .amend(Some("HEAD"), None, Some(&alice), None, None, None)
+            .unwrap();
+
+        let config = RepositoryConfig {
+            include_reflog: true,
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        assert_eq!(
+            commit_counts(&stats),
+            HashMap::from([
+                ("alice@example.com", 1),
+                ("erin@example.com", 1),
+                ("frank@example.com", 1)
+            ])
+        );
+        let frank_info = &stats
+            .authors
+            .iter()
+            .find(|(email, _)| email == "frank@example.com")
+            .unwrap()
+            .1;
+        assert_eq!(frank_info.first_commit, time_date(frank.when()).unwrap());
+    }
+
+    #[test]
+    fn test_include_reflog_applies_history_filters() {
+        let (_dir, repo) = init_repo();
+        let alice = Signature::new("Alice", "alice@example.com", &Time::new(1_000_000, 0)).unwrap();
+        let bob = Signature::new("Bob", "bob@example.com", &Time::new(1_100_000, 0)).unwrap();
+        let dave = Signature::new("Dave", "dave@example.com", &Time::new(1_200_000, 0)).unwrap();
+        commit_as(&repo, "HEAD", &alice, "Initial import", &[]);
+        let draft = commit_as(&repo, "HEAD", &bob, "Draft", &[]);
+        repo.find_commit(draft)
+            .unwrap()
+            .amend(Some("HEAD"), Some(&dave), None, None, None, None)
+            .unwrap();
+
+        let config = RepositoryConfig {
+            include_reflog: true,
+            skip_root: true,
+            top_words: Some(10),
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        assert_eq!(
+            commit_counts(&stats),
+            HashMap::from([("bob@example.com", 1), ("dave@example.com", 1)])
+        );
+        assert!(stats
+            .authors
+            .iter()
+            .all(|(_, info)| info.average_message_length() == 5.0));
+
+        let config = RepositoryConfig {
+            include_reflog: true,
+            max_commits: Some(2),
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        assert_eq!(total_commits(&stats), 2);
+        assert!(stats.limited);
+
+        let config = RepositoryConfig {
+            include_reflog: true,
+            head: Some("HEAD".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            collect_commit_info(&repo, &config),
+            Err(RepositoryError::ConflictingOptions(..))
+        ));
+    }
+
+    /// Alice and Bob commit on diverging branches, then Carol merges Bob's branch into main
+    fn merge_feature_branch(repo: &Repository) {
+        let base = commit(repo, "refs/heads/main", "alice@example.com", 1_000_000, &[]);
+        repo.reference("refs/heads/feature", base, false, "branch")
+            .unwrap();
+        let feature = commit(
+            repo,
+            "refs/heads/feature",
+            "bob@example.com",
+            1_100_000,
+            &[("feature.txt", "feature")],
+        );
+        let main = commit(
+            repo,
+            "refs/heads/main",
+            "alice@example.com",
+            1_200_000,
+            &[("main.txt", "main")],
+        );
+
+        let (main, feature) = (
+            repo.find_commit(main).unwrap(),
+            repo.find_commit(feature).unwrap(),
+        );
+        let mut index = repo.merge_commits(&main, &feature, None).unwrap();
+        let tree = repo.find_tree(index.write_tree_to(repo).unwrap()).unwrap();
+        let carol = Signature::new("Carol", "carol@example.com", &Time::new(1_300_000, 0)).unwrap();
+        repo.commit(
+            Some("refs/heads/main"),
+            &carol,
+            &carol,
+            "Merge feature",
+            &tree,
+            &[&main, &feature],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_merge_handling() {
+        let (_dir, repo) = init_repo();
+        merge_feature_branch(&repo);
+
+        let config = |merge_handling| RepositoryConfig {
+            merge_handling,
+            ..Default::default()
+        };
+
+        let stats = collect_commit_info(&repo, &config(MergeHandling::Count)).unwrap();
+        assert_eq!(
+            commit_counts(&stats),
+            HashMap::from([
+                ("alice@example.com", 2),
+                ("bob@example.com", 1),
+                ("carol@example.com", 1)
+            ])
+        );
+
+        let stats = collect_commit_info(&repo, &config(MergeHandling::Skip)).unwrap();
+        assert_eq!(
+            commit_counts(&stats),
+            HashMap::from([("alice@example.com", 2), ("bob@example.com", 1)])
+        );
+
+        let stats = collect_commit_info(&repo, &config(MergeHandling::Bucket)).unwrap();
+        assert_eq!(
+            commit_counts(&stats),
+            HashMap::from([
+                ("alice@example.com", 2),
+                ("bob@example.com", 1),
+                ("(merges)", 1)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_merges_are_not_diffed() {
+        let (_dir, repo) = init_repo();
+        merge_feature_branch(&repo);
+
+        let config = RepositoryConfig {
+            files_touched: true,
+            files_changed: true,
+            by_extension: true,
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        let files: HashMap<&str, (usize, usize)> = stats
+            .authors
+            .iter()
+            .map(|(email, info)| (email.as_str(), (info.files_touched, info.files_changed)))
+            .collect();
+        assert_eq!(files["carol@example.com"], (0, 0));
+        assert_eq!(files["bob@example.com"], (1, 1));
+        assert_eq!(stats.commits_by_extension.values().sum::<u32>(), 2);
+
+        let config = RepositoryConfig {
+            follow: Some("feature.txt".to_string()),
+            ..Default::default()
+        };
+        let stats = collect_commit_info(&repo, &config).unwrap();
+        assert_eq!(
+            commit_counts(&stats),
+            HashMap::from([("bob@example.com", 1)])
+        );
+    }
 }
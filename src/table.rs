@@ -0,0 +1,902 @@
+use chrono::{NaiveDate, TimeDelta};
+use std::cmp::Ordering;
+use std::io::{self, Write};
+
+use crate::locale::Locale;
+use crate::team::Team;
+use crate::user_commit_info::UserCommitInfo;
+
+/// Options controlling how the author table is rendered
+#[derive(Debug)]
+pub struct TableOptions {
+    /// Fixed total table width, if any
+    pub width: Option<usize>,
+    /// Flag authors whose last commit is more than this many days before `today`
+    pub inactive_days: Option<i64>,
+    pub today: NaiveDate,
+    /// Draw a bar next to each commit count, scaled to the largest count
+    pub bars: bool,
+    /// Show how many distinct files each author changed
+    pub files_touched: bool,
+    /// Show how many files each author's commits changed in total
+    pub files_changed: bool,
+    /// Show how many distinct names each author committed under
+    pub aliases: bool,
+    /// Abbreviate large commit counts, e.g. `12.3k`
+    pub compact: bool,
+    /// Show how many annotated tags each author created
+    pub tags: bool,
+    /// Show each author's average and longest commit message length
+    pub message_lengths: bool,
+    /// Show each author's longest stretch of days without a commit
+    pub longest_gap: bool,
+    /// Show each author's average commits per week between their first and last commit
+    pub weekly_rate: bool,
+    /// Show whether each author committed more in the second half of the overall span
+    pub trend: bool,
+    /// Show the lines each author changed in files with this extension, e.g. `.rs`
+    pub weight_ext: Option<String>,
+    /// Show how evenly each author's commits are spread over the days they were active
+    pub spread: bool,
+    /// Show a sparkline of each author's commits over the last twelve months
+    pub sparkline: bool,
+    /// Mark this author's row, e.g. the configured `user.email`
+    pub current_user: Option<String>,
+    /// Separators and date format for numbers and dates
+    pub locale: Locale,
+    /// Mark authors listed in this team
+    pub team: Option<Team>,
+    /// Columns to show first, in this order
+    pub column_order: Vec<Column>,
+}
+
+impl TableOptions {
+    fn format_count(&self, count: u32) -> String {
+        if self.compact {
+            self.locale.localize_decimal(compact_count(count))
+        } else {
+            self.locale.format_number(count.into())
+        }
+    }
+
+    fn columns(&self) -> Vec<Column> {
+        let mut columns = vec![
+            Column::Email,
+            Column::Commits,
+            Column::First,
+            Column::Last,
+            Column::Days,
+        ];
+        if self.files_touched {
+            columns.push(Column::Files);
+        }
+        if self.files_changed {
+            columns.push(Column::Changed);
+        }
+        if self.aliases {
+            columns.push(Column::Names);
+        }
+        if self.tags {
+            columns.push(Column::Tags);
+        }
+        if self.weight_ext.is_some() {
+            columns.push(Column::Weighted);
+        }
+        if self.message_lengths {
+            columns.push(Column::AvgMessage);
+            columns.push(Column::MaxMessage);
+        }
+        if self.longest_gap {
+            columns.push(Column::Gap);
+        }
+        if self.weekly_rate {
+            columns.push(Column::PerWeek);
+        }
+        if self.trend {
+            columns.push(Column::Trend);
+        }
+        if self.spread {
+            columns.push(Column::Spread);
+        }
+        if self.sparkline {
+            columns.push(Column::Activity);
+        }
+
+        // Listed columns come first in the requested order, unlisted ones keep their place after
+        columns.sort_by_key(|column| {
+            self.column_order
+                .iter()
+                .position(|c| c == column)
+                .unwrap_or(usize::MAX)
+        });
+        columns
+    }
+
+    fn has_markers(&self) -> bool {
+        self.inactive_days.is_some() || self.current_user.is_some() || self.team.is_some()
+    }
+
+    fn marker(&self, email: &str, user_commit_info: &UserCommitInfo) -> char {
+        let inactive = self
+            .inactive_days
+            .is_some_and(|days| user_commit_info.days_since_last(self.today) > days);
+
+        if self.current_user.as_deref() == Some(email) {
+            '*'
+        } else if inactive {
+            '!'
+        } else if self.team.as_ref().is_some_and(|team| team.contains(email)) {
+            '+'
+        } else {
+            ' '
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Column {
+    Email,
+    Commits,
+    First,
+    Last,
+    Days,
+    Files,
+    Changed,
+    Names,
+    Tags,
+    Weighted,
+    AvgMessage,
+    MaxMessage,
+    Gap,
+    PerWeek,
+    Trend,
+    Spread,
+    Activity,
+}
+
+impl Column {
+    fn header(self) -> &'static str {
+        match self {
+            Column::Email => "Email",
+            Column::Commits => "Commits",
+            Column::First => "First",
+            Column::Last => "Last",
+            Column::Days => "Days",
+            Column::Files => "Files",
+            Column::Changed => "Changed",
+            Column::Names => "Names",
+            Column::Tags => "Tags",
+            Column::Weighted => "Lines",
+            Column::AvgMessage => "Avg msg",
+            Column::MaxMessage => "Max msg",
+            Column::Gap => "Gap",
+            Column::PerWeek => "Per week",
+            Column::Trend => "Trend",
+            Column::Spread => "Spread",
+            Column::Activity => "Last 12 months",
+        }
+    }
+
+    /// Natural width, used when no fixed total width is requested
+    fn width(self) -> usize {
+        match self {
+            Column::Email => 55,
+            Column::Commits => 10,
+            Column::First | Column::Last => 12,
+            Column::Days => 5,
+            Column::Files | Column::Names | Column::Tags | Column::Gap | Column::Trend => 6,
+            Column::Changed | Column::AvgMessage | Column::MaxMessage | Column::Spread => 8,
+            Column::Weighted => 10,
+            Column::PerWeek | Column::Activity => 14,
+        }
+    }
+}
+
+const ELLIPSIS: char = '…';
+
+/// Abbreviate `count` to at most one decimal with a `k`, `M` or `B` suffix, e.g. `12.3k`
+fn compact_count(count: u32) -> String {
+    const UNITS: [(f64, &str); 3] = [(1e3, "k"), (1e6, "M"), (1e9, "B")];
+
+    if count < 1000 {
+        return count.to_string();
+    }
+
+    let mut scaled = 0.0;
+    let mut suffix = "";
+    for (scale, unit) in UNITS {
+        scaled = (count as f64 / scale * 10.0).round() / 10.0;
+        suffix = unit;
+        // Carry over to the next unit when rounding reaches 1000, e.g. 999,950 becomes 1M
+        if scaled < 1000.0 {
+            break;
+        }
+    }
+
+    if scaled.fract() == 0.0 {
+        format!("{}{}", scaled, suffix)
+    } else {
+        format!("{:.1}{}", scaled, suffix)
+    }
+}
+
+/// The earliest first commit and the latest last commit of all authors, or `None` without authors
+fn date_span(commits: &[(String, UserCommitInfo)]) -> Option<(NaiveDate, NaiveDate)> {
+    let first = commits.iter().map(|(_, c)| c.first_commit).min()?;
+    let last = commits.iter().map(|(_, c)| c.last_commit).max()?;
+    Some((first, last))
+}
+
+/// The last day of the first half and the first day of the second half of the days from the
+/// earliest first commit to the latest last commit of all authors. With an odd number of days
+/// the middle one belongs to neither half, and a single day has no halves.
+fn span_halves(commits: &[(String, UserCommitInfo)]) -> Option<(NaiveDate, NaiveDate)> {
+    let (first, last) = date_span(commits)?;
+    let days = (last - first).num_days() + 1;
+    if days < 2 {
+        return None;
+    }
+
+    Some((
+        first + TimeDelta::days(days / 2 - 1),
+        first + TimeDelta::days((days + 1) / 2),
+    ))
+}
+
+/// `↑` when an author committed more in the second half than in the first, `↓` when less,
+/// `→` otherwise
+fn trend_arrow(before: u32, after: u32) -> char {
+    match after.cmp(&before) {
+        Ordering::Greater => '↑',
+        Ordering::Less => '↓',
+        Ordering::Equal => '→',
+    }
+}
+
+/// Number of months covered by the activity sparkline
+const SPARKLINE_MONTHS: usize = 12;
+
+const SPARKLINE_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Render `series` as one block character per value, scaled to its largest value; zeros are blank
+fn sparkline(series: &[u32]) -> String {
+    let max = series.iter().copied().max().unwrap_or(0);
+
+    series
+        .iter()
+        .map(|&value| {
+            if value == 0 {
+                ' '
+            } else {
+                let level = (value as f64 / max as f64 * SPARKLINE_LEVELS.len() as f64).ceil();
+                SPARKLINE_LEVELS[level as usize - 1]
+            }
+        })
+        .collect()
+}
+
+/// Number of cells used by a full-length commit bar
+const BAR_WIDTH: usize = 10;
+
+/// Partial blocks for each additional eighth of a cell
+const BAR_EIGHTHS: [&str; 8] = ["", "▏", "▎", "▍", "▌", "▋", "▊", "▉"];
+
+/// Render `value` as a bar of block characters, where `max` fills `BAR_WIDTH` cells
+fn commit_bar(value: u32, max: u32) -> String {
+    if max == 0 || value == 0 {
+        return String::new();
+    }
+
+    let eighths = (value as f64 / max as f64 * (BAR_WIDTH * 8) as f64).round() as usize;
+    // Never hide an author with commits entirely
+    let eighths = eighths.max(1);
+
+    let mut bar = "█".repeat(eighths / 8);
+    bar.push_str(BAR_EIGHTHS[eighths % 8]);
+    bar
+}
+
+/// Narrowest the email column may get under a fixed width, enough for its header
+const MIN_EMAIL_WIDTH: usize = 5;
+
+/// Fit the columns into `total_width`: every column keeps the width of its content, and the
+/// `flexible` (email) column takes whatever is left, truncating long values. Fails with the
+/// smallest usable total width when the other columns leave less than `MIN_EMAIL_WIDTH`.
+fn fixed_column_widths(
+    content_widths: &[usize],
+    flexible: usize,
+    total_width: usize,
+) -> Result<Vec<usize>, usize> {
+    let separators = content_widths.len() - 1;
+    let others: usize = content_widths
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| i != flexible)
+        .map(|(_, width)| width)
+        .sum();
+
+    let minimum = separators + others + MIN_EMAIL_WIDTH;
+    if total_width < minimum {
+        return Err(minimum);
+    }
+
+    let mut widths = content_widths.to_vec();
+    widths[flexible] = total_width - separators - others;
+    Ok(widths)
+}
+
+fn truncate(value: &str, width: usize) -> String {
+    if value.chars().count() <= width {
+        return value.to_owned();
+    }
+
+    let mut truncated: String = value.chars().take(width.saturating_sub(1)).collect();
+    truncated.push(ELLIPSIS);
+    truncated
+}
+
+fn write_row<W: Write>(
+    out: &mut W,
+    cells: &[String],
+    widths: &[usize],
+    fixed_width: bool,
+) -> io::Result<()> {
+    let line = cells
+        .iter()
+        .zip(widths)
+        .map(|(cell, &width)| {
+            let cell = if fixed_width {
+                truncate(cell, width)
+            } else {
+                cell.clone()
+            };
+            format!("{:<width$}", cell, width = width)
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    writeln!(out, "{}", line)
+}
+
+fn write_commits<W: Write>(
+    out: &mut W,
+    commits: &[(String, UserCommitInfo)],
+    options: &TableOptions,
+) -> io::Result<()> {
+    let max_commits = commits.iter().map(|(_, c)| c.commits).max().unwrap_or(0);
+    let count_width = commits
+        .iter()
+        .map(|(_, c)| options.format_count(c.commits).chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let number = |value: i64| options.locale.format_number(value);
+    let halves = span_halves(commits);
+
+    let columns = options.columns();
+    let headers: Vec<String> = columns
+        .iter()
+        .map(|&column| match (column, &options.weight_ext) {
+            (Column::Weighted, Some(extension)) => format!("{} lines", extension),
+            _ => column.header().to_string(),
+        })
+        .collect();
+    let rows: Vec<Vec<String>> = commits
+        .iter()
+        .map(|(email, user_commit_info)| {
+            columns
+                .iter()
+                .map(|column| match column {
+                    Column::Email if options.has_markers() => {
+                        format!("{} {}", options.marker(email, user_commit_info), email)
+                    }
+                    Column::Email => email.clone(),
+                    Column::Commits if options.bars => format!(
+                        "{:<count_width$} {}",
+                        options.format_count(user_commit_info.commits),
+                        commit_bar(user_commit_info.commits, max_commits),
+                        count_width = count_width
+                    ),
+                    Column::Commits => options.format_count(user_commit_info.commits),
+                    Column::First => user_commit_info
+                        .first_commit
+                        .format(options.locale.date_format)
+                        .to_string(),
+                    Column::Last => user_commit_info
+                        .last_commit
+                        .format(options.locale.date_format)
+                        .to_string(),
+                    Column::Days => number(user_commit_info.days_between()),
+                    Column::Files => number(user_commit_info.files_touched as i64),
+                    Column::Changed => number(user_commit_info.files_changed as i64),
+                    Column::Names => number(user_commit_info.aliases as i64),
+                    Column::Tags => number(user_commit_info.tags as i64),
+                    Column::Weighted => number(user_commit_info.weighted_lines as i64),
+                    Column::AvgMessage => options.locale.localize_decimal(format!(
+                        "{:.1}",
+                        user_commit_info.average_message_length()
+                    )),
+                    Column::MaxMessage => number(user_commit_info.message_length_max as i64),
+                    Column::Gap => number(user_commit_info.longest_gap()),
+                    Column::PerWeek => match user_commit_info.commits_per_week() {
+                        Some(rate) => options.locale.localize_decimal(format!("{:.1}", rate)),
+                        None => format!(
+                            "{} in one day",
+                            options.format_count(user_commit_info.commits)
+                        ),
+                    },
+                    Column::Trend => {
+                        let (before, after) = halves.map_or((0, 0), |(end, start)| {
+                            user_commit_info.commits_in_halves(end, start)
+                        });
+                        trend_arrow(before, after).to_string()
+                    }
+                    Column::Spread => options
+                        .locale
+                        .localize_decimal(format!("{:.2}", user_commit_info.spread())),
+                    Column::Activity => {
+                        sparkline(&user_commit_info.monthly_series(options.today, SPARKLINE_MONTHS))
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    let widths: Vec<usize> = match options.width {
+        Some(total_width) => {
+            let content_widths: Vec<usize> = (0..columns.len())
+                .map(|i| {
+                    rows.iter()
+                        .map(|row| row[i].chars().count())
+                        .chain(std::iter::once(headers[i].chars().count()))
+                        .max()
+                        .unwrap_or(0)
+                })
+                .collect();
+            let email = columns
+                .iter()
+                .position(|&column| column == Column::Email)
+                .unwrap_or(0);
+            fixed_column_widths(&content_widths, email, total_width).map_err(|minimum| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "--width {} is too narrow; the selected columns need at least {}",
+                        total_width, minimum
+                    ),
+                )
+            })?
+        }
+        None => columns
+            .iter()
+            .map(|&column| match column {
+                Column::Commits if options.bars => column.width().max(count_width + 1 + BAR_WIDTH),
+                _ => column.width(),
+            })
+            .collect(),
+    };
+    let fixed_width = options.width.is_some();
+
+    write_row(out, &headers, &widths, fixed_width)?;
+    for cells in &rows {
+        write_row(out, cells, &widths, fixed_width)?;
+    }
+
+    Ok(())
+}
+
+/// Order by first commit, then most recent last commit, then email so ties are reproducible
+fn sort_commits(commits: &mut [(String, UserCommitInfo)]) {
+    commits.sort_by(|(a_email, a), (b_email, b)| {
+        a.first_commit
+            .cmp(&b.first_commit)
+            .then(a.last_commit.cmp(&b.last_commit).reverse())
+            .then(a_email.cmp(b_email))
+    });
+}
+
+/// Print the author table; a closed pipe, e.g. from `| head`, is not an error
+pub fn print_commits(
+    mut commits: Vec<(String, UserCommitInfo)>,
+    options: &TableOptions,
+) -> io::Result<()> {
+    sort_commits(&mut commits);
+
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    match write_commits(&mut stdout, &commits, options) {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(width: Option<usize>) -> TableOptions {
+        TableOptions {
+            width,
+            inactive_days: None,
+            today: NaiveDate::from_ymd_opt(2023, 6, 1).unwrap(),
+            bars: false,
+            files_touched: false,
+            files_changed: false,
+            aliases: false,
+            compact: false,
+            tags: false,
+            message_lengths: false,
+            longest_gap: false,
+            weekly_rate: false,
+            trend: false,
+            spread: false,
+            weight_ext: None,
+            sparkline: false,
+            current_user: None,
+            locale: Locale::default(),
+            team: None,
+            column_order: Vec::new(),
+        }
+    }
+
+    fn sample_commits() -> Vec<(String, UserCommitInfo)> {
+        let date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        vec![
+            ("alice@example.com".to_string(), UserCommitInfo::new(date)),
+            (
+                "a.very.long.email.address.for.testing@subdomain.example.com".to_string(),
+                UserCommitInfo::new(date),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_fixed_width_output() {
+        let mut output = Vec::new();
+        write_commits(&mut output, &sample_commits(), &options(Some(60))).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        for line in output.lines() {
+            assert_eq!(line.chars().count(), 60);
+        }
+
+        let long_row = output.lines().nth(2).unwrap();
+        assert!(long_row.starts_with("a.very.long.email"));
+        assert!(long_row.contains(ELLIPSIS));
+
+        // Only the email column gives up space; headers, counts and dates stay intact
+        let headers: Vec<&str> = output.lines().next().unwrap().split_whitespace().collect();
+        assert_eq!(headers, ["Email", "Commits", "First", "Last", "Days"]);
+        let cells: Vec<&str> = long_row.split_whitespace().collect();
+        assert_eq!(cells[1..], ["1", "01/01/2023", "01/01/2023", "0"]);
+        assert!(output
+            .lines()
+            .nth(1)
+            .unwrap()
+            .starts_with("alice@example.com "));
+    }
+
+    #[test]
+    fn test_too_narrow_width_is_an_error() {
+        for width in [0, 10, 39] {
+            let mut output = Vec::new();
+            let error =
+                write_commits(&mut output, &sample_commits(), &options(Some(width))).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+            assert!(error.to_string().contains("at least 40"));
+            assert!(output.is_empty());
+        }
+
+        let mut output = Vec::new();
+        write_commits(&mut output, &sample_commits(), &options(Some(40))).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.lines().all(|line| line.chars().count() == 40));
+        assert!(output.starts_with("Email "));
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("alice@example.com", 20), "alice@example.com");
+        assert_eq!(truncate("alice@example.com", 6), "alice…");
+    }
+
+    #[test]
+    fn test_inactive_marker() {
+        let mut recent = UserCommitInfo::new(NaiveDate::from_ymd_opt(2023, 1, 1).unwrap());
+        recent.update(NaiveDate::from_ymd_opt(2023, 5, 20).unwrap());
+        let commits = vec![
+            (
+                "stale@example.com".to_string(),
+                sample_commits().remove(0).1,
+            ),
+            ("recent@example.com".to_string(), recent),
+        ];
+
+        let mut output = Vec::new();
+        let options = TableOptions {
+            inactive_days: Some(30),
+            ..options(None)
+        };
+        write_commits(&mut output, &commits, &options).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output
+            .lines()
+            .nth(1)
+            .unwrap()
+            .starts_with("! stale@example.com"));
+        assert!(output
+            .lines()
+            .nth(2)
+            .unwrap()
+            .starts_with("  recent@example.com"));
+    }
+
+    #[test]
+    fn test_current_user_marker() {
+        let commits = sample_commits();
+        let current = commits[1].0.clone();
+
+        let mut output = Vec::new();
+        let options = TableOptions {
+            current_user: Some(current.clone()),
+            ..options(None)
+        };
+        write_commits(&mut output, &commits, &options).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.lines().nth(1).unwrap().starts_with("  "));
+        assert!(output
+            .lines()
+            .nth(2)
+            .unwrap()
+            .starts_with(&format!("* {}", current)));
+    }
+
+    #[test]
+    fn test_team_marker() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("team.txt");
+        std::fs::write(&path, "Alice@Example.com\n").unwrap();
+
+        let options = TableOptions {
+            team: Some(Team::load(path.to_str().unwrap()).unwrap()),
+            ..options(None)
+        };
+        let (_, info) = &sample_commits()[0];
+        assert_eq!(options.marker("alice@example.com", info), '+');
+        assert_eq!(options.marker("bob@example.com", info), ' ');
+
+        let options = TableOptions {
+            current_user: Some("alice@example.com".to_owned()),
+            ..options
+        };
+        assert_eq!(options.marker("alice@example.com", info), '*');
+    }
+
+    #[test]
+    fn test_column_order() {
+        let mut output = Vec::new();
+        let options = TableOptions {
+            column_order: vec![Column::Commits, Column::Last, Column::Email],
+            ..options(Some(100))
+        };
+        write_commits(&mut output, &sample_commits()[..1], &options).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let mut lines = output.lines();
+
+        let headers: Vec<&str> = lines.next().unwrap().split_whitespace().collect();
+        assert_eq!(headers, ["Commits", "Last", "Email", "First", "Days"]);
+
+        let cells: Vec<&str> = lines.next().unwrap().split_whitespace().collect();
+        assert_eq!(
+            cells,
+            ["1", "01/01/2023", "alice@example.com", "01/01/2023", "0"]
+        );
+        assert!(output.lines().all(|line| line.chars().count() == 100));
+    }
+
+    #[test]
+    fn test_weekly_rate_column() {
+        let date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let mut steady = UserCommitInfo::new(date);
+        for _ in 0..4 {
+            steady.update(NaiveDate::from_ymd_opt(2023, 1, 29).unwrap());
+        }
+        let mut burst = UserCommitInfo::new(date);
+        burst.update(date);
+        let commits = vec![
+            ("steady@example.com".to_string(), steady),
+            ("burst@example.com".to_string(), burst),
+        ];
+
+        let mut output = Vec::new();
+        let options = TableOptions {
+            weekly_rate: true,
+            ..options(None)
+        };
+        write_commits(&mut output, &commits, &options).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert!(lines[0].trim_end().ends_with("Per week"));
+        assert!(lines[1].trim_end().ends_with(" 1.2"));
+        assert!(lines[2].trim_end().ends_with(" 2 in one day"));
+    }
+
+    /// An author with one commit on each of `days` in January 2023, with commit days recorded
+    fn active_on(days: &[u32]) -> UserCommitInfo {
+        let dates: Vec<NaiveDate> = days
+            .iter()
+            .map(|&day| NaiveDate::from_ymd_opt(2023, 1, day).unwrap())
+            .collect();
+        let mut user_commit_info = UserCommitInfo::new(dates[0]);
+        user_commit_info.record_day(dates[0]);
+        for &date in &dates[1..] {
+            user_commit_info.update(date);
+            user_commit_info.record_day(date);
+        }
+        user_commit_info
+    }
+
+    #[test]
+    fn test_date_span() {
+        assert_eq!(date_span(&[]), None);
+
+        let commits = vec![
+            ("alice@example.com".to_string(), active_on(&[10, 12])),
+            ("bob@example.com".to_string(), active_on(&[3, 5])),
+            ("carol@example.com".to_string(), active_on(&[20])),
+        ];
+        assert_eq!(
+            date_span(&commits),
+            Some((
+                NaiveDate::from_ymd_opt(2023, 1, 3).unwrap(),
+                NaiveDate::from_ymd_opt(2023, 1, 20).unwrap()
+            ))
+        );
+    }
+
+    /// The trend arrow of every row, in order
+    fn trends(commits: &[(String, UserCommitInfo)]) -> Vec<String> {
+        let mut output = Vec::new();
+        let options = TableOptions {
+            trend: true,
+            ..options(None)
+        };
+        write_commits(&mut output, commits, &options).unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .skip(1)
+            .map(|line| line.split_whitespace().last().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_trend_column() {
+        let commits = vec![
+            ("early@example.com".to_string(), active_on(&[1, 2, 3, 20])),
+            ("late@example.com".to_string(), active_on(&[5, 25, 30, 31])),
+            ("steady@example.com".to_string(), active_on(&[10, 22])),
+        ];
+        assert_eq!(trends(&commits), ["↓", "↑", "→"]);
+    }
+
+    #[test]
+    fn test_trend_of_steady_authors() {
+        for days in [&[1, 2, 3][..], &[1, 2, 3, 4], &[1, 2, 3, 4, 5]] {
+            let commits = vec![("steady@example.com".to_string(), active_on(days))];
+            assert_eq!(trends(&commits), ["→"], "{:?}", days);
+        }
+    }
+
+    #[test]
+    fn test_trend_over_a_single_day() {
+        let commits = vec![
+            ("alice@example.com".to_string(), active_on(&[5, 5, 5])),
+            ("bob@example.com".to_string(), active_on(&[5])),
+        ];
+        assert_eq!(trends(&commits), ["→", "→"]);
+    }
+
+    #[test]
+    fn test_trend_arrow() {
+        assert_eq!(trend_arrow(3, 1), '↓');
+        assert_eq!(trend_arrow(1, 3), '↑');
+        assert_eq!(trend_arrow(2, 2), '→');
+        assert_eq!(trend_arrow(0, 0), '→');
+    }
+
+    #[test]
+    fn test_locale_applies_to_every_number() {
+        let mut user_commit_info =
+            UserCommitInfo::new(NaiveDate::from_ymd_opt(2020, 1, 1).unwrap());
+        user_commit_info.update(NaiveDate::from_ymd_opt(2023, 6, 1).unwrap());
+        user_commit_info.files_touched = 12_345;
+        user_commit_info.files_changed = 23_456;
+        user_commit_info.record_message_length(5);
+        let commits = vec![("alice@example.com".to_string(), user_commit_info)];
+
+        let mut output = Vec::new();
+        let options = TableOptions {
+            locale: Locale::from_tag("de").unwrap(),
+            files_touched: true,
+            files_changed: true,
+            message_lengths: true,
+            ..options(None)
+        };
+        write_commits(&mut output, &commits, &options).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        let cells: Vec<&str> = output.lines().nth(1).unwrap().split_whitespace().collect();
+        assert_eq!(
+            cells,
+            [
+                "alice@example.com",
+                "2",
+                "01.01.2020",
+                "01.06.2023",
+                "1.247",
+                "12.345",
+                "23.456",
+                "2,5",
+                "5"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sort_breaks_ties_by_email() {
+        let date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let mut earlier = UserCommitInfo::new(date.pred_opt().unwrap());
+        earlier.update(date);
+        let mut commits = vec![
+            ("carol@example.com".to_string(), UserCommitInfo::new(date)),
+            ("alice@example.com".to_string(), UserCommitInfo::new(date)),
+            ("dave@example.com".to_string(), earlier),
+            ("bob@example.com".to_string(), UserCommitInfo::new(date)),
+        ];
+
+        sort_commits(&mut commits);
+        let emails: Vec<&str> = commits.iter().map(|(email, _)| email.as_str()).collect();
+        assert_eq!(
+            emails,
+            [
+                "dave@example.com",
+                "alice@example.com",
+                "bob@example.com",
+                "carol@example.com"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_commit_bar() {
+        assert_eq!(commit_bar(0, 10), "");
+        assert_eq!(commit_bar(10, 10), "██████████");
+        assert_eq!(commit_bar(5, 10), "█████");
+        assert_eq!(commit_bar(1, 16), "▋");
+        assert_eq!(commit_bar(3, 16), "█▉");
+        assert_eq!(commit_bar(1, 1000), "▏");
+    }
+
+    #[test]
+    fn test_compact_count() {
+        assert_eq!(compact_count(999), "999");
+        assert_eq!(compact_count(1000), "1k");
+        assert_eq!(compact_count(12345), "12.3k");
+        assert_eq!(compact_count(999_950), "1M");
+        assert_eq!(compact_count(1_234_567), "1.2M");
+    }
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[0, 1, 2, 4, 8]), " ▁▂▄█");
+        assert_eq!(sparkline(&[3, 0, 3]), "█ █");
+        assert_eq!(sparkline(&[0, 0]), "  ");
+        assert_eq!(sparkline(&[]), "");
+    }
+}
@@ -0,0 +1,65 @@
+use std::collections::HashSet;
+use std::fs;
+
+/// Emails listed in a `--team` file, one per line; blank lines and `#` comments are ignored
+#[derive(Debug, Default)]
+pub struct Team {
+    emails: HashSet<String>,
+}
+
+impl Team {
+    pub fn load(path: &str) -> Result<Team, String> {
+        fs::read_to_string(path)
+            .map(|contents| Team::parse(&contents))
+            .map_err(|e| format!("Could not read the team file '{}': {}", path, e))
+    }
+
+    fn parse(contents: &str) -> Team {
+        Team::from_emails(
+            contents
+                .lines()
+                .map(|line| line.split('#').next().unwrap_or_default().trim())
+                .filter(|line| !line.is_empty())
+                .map(str::to_owned),
+        )
+    }
+
+    pub fn from_emails(emails: impl IntoIterator<Item = String>) -> Team {
+        let emails = emails
+            .into_iter()
+            .map(|email| email.to_lowercase())
+            .collect();
+
+        Team { emails }
+    }
+
+    /// The team with every listed email passed through `key`, e.g. to match how authors are keyed
+    pub fn map_emails(&self, key: impl Fn(&str) -> String) -> Team {
+        Team::from_emails(self.emails.iter().map(|email| key(email)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.emails.len()
+    }
+
+    /// Whether `email` is listed, ignoring case
+    pub fn contains(&self, email: &str) -> bool {
+        self.emails.contains(&email.to_lowercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_skips_comments_and_blank_lines() {
+        let team =
+            Team::parse("# Platform team\nalice@example.com\n\n  Bob@Example.com  # on leave\n");
+
+        assert_eq!(team.emails.len(), 2);
+        assert!(team.contains("alice@example.com"));
+        assert!(team.contains("bob@example.com"));
+        assert!(!team.contains("carol@example.com"));
+    }
+}
@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+/// Format a UTC offset in minutes as a label such as `UTC+2`, `UTC-3:30` or `UTC`
+pub fn format_offset(offset_minutes: i32) -> String {
+    if offset_minutes == 0 {
+        return "UTC".to_string();
+    }
+
+    let sign = if offset_minutes < 0 { '-' } else { '+' };
+    let hours = offset_minutes.abs() / 60;
+    let minutes = offset_minutes.abs() % 60;
+
+    if minutes == 0 {
+        format!("UTC{}{}", sign, hours)
+    } else {
+        format!("UTC{}{}:{:02}", sign, hours, minutes)
+    }
+}
+
+pub fn print_timezones(timezone_counts: &HashMap<i32, u32>) {
+    let mut offsets: Vec<(&i32, &u32)> = timezone_counts.iter().collect();
+    offsets.sort();
+
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    let result = writeln!(stdout, "\n{:<12} {:<10}", "Timezone", "Commits").and_then(|_| {
+        offsets.into_iter().try_for_each(|(offset, count)| {
+            writeln!(stdout, "{:<12} {:<10}", format_offset(*offset), count)
+        })
+    });
+
+    if let Err(e) = result {
+        if e.kind() != io::ErrorKind::BrokenPipe {
+            eprintln!("Error writing to stdout: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_offset() {
+        assert_eq!(format_offset(0), "UTC");
+        assert_eq!(format_offset(120), "UTC+2");
+        assert_eq!(format_offset(-300), "UTC-5");
+        assert_eq!(format_offset(330), "UTC+5:30");
+        assert_eq!(format_offset(-210), "UTC-3:30");
+    }
+}
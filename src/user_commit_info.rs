@@ -1,10 +1,25 @@
-use chrono::NaiveDate;
+use chrono::{Datelike, NaiveDate};
+use std::collections::BTreeMap;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct UserCommitInfo {
     pub commits: u32,
     pub first_commit: NaiveDate,
     pub last_commit: NaiveDate,
+    pub files_touched: usize,
+    /// Files changed summed over every commit, so a file changed twice counts twice
+    pub files_changed: usize,
+    pub aliases: usize,
+    /// Lines added or deleted in files with the weighted extension, when one is given
+    pub weighted_lines: usize,
+    pub tags: usize,
+    /// Total and longest commit message length, when messages are analyzed
+    pub message_length_total: usize,
+    pub message_length_max: usize,
+    /// Commit counts keyed by `(year, month)`, when monthly counts are recorded
+    pub commits_by_month: BTreeMap<(i32, u32), u32>,
+    /// Commit counts for each day with at least one commit, when commit days are recorded
+    pub commit_days: BTreeMap<NaiveDate, u32>,
 }
 
 impl UserCommitInfo {
@@ -13,6 +28,15 @@ impl UserCommitInfo {
             commits: 1,
             first_commit: commit_time,
             last_commit: commit_time,
+            files_touched: 0,
+            files_changed: 0,
+            aliases: 0,
+            weighted_lines: 0,
+            tags: 0,
+            message_length_total: 0,
+            message_length_max: 0,
+            commits_by_month: BTreeMap::new(),
+            commit_days: BTreeMap::new(),
         }
     }
 
@@ -28,9 +52,113 @@ impl UserCommitInfo {
         }
     }
 
+    pub fn record_month(&mut self, commit_time: NaiveDate) {
+        *self
+            .commits_by_month
+            .entry((commit_time.year(), commit_time.month()))
+            .or_insert(0) += 1;
+    }
+
+    pub fn record_day(&mut self, commit_time: NaiveDate) {
+        *self.commit_days.entry(commit_time).or_insert(0) += 1;
+    }
+
+    pub fn record_message_length(&mut self, length: usize) {
+        self.message_length_total += length;
+        self.message_length_max = self.message_length_max.max(length);
+    }
+
+    /// Average commit message length, if message lengths were recorded for every commit
+    pub fn average_message_length(&self) -> f64 {
+        self.message_length_total as f64 / self.commits as f64
+    }
+
     pub fn days_between(&self) -> i64 {
         (self.last_commit - self.first_commit).num_days()
     }
+
+    /// Commits per week between the first and last commit, or `None` when they fall on one day.
+    /// Spans shorter than a week count as one week, so a short burst isn't extrapolated.
+    pub fn commits_per_week(&self) -> Option<f64> {
+        let days = self.days_between();
+        if days == 0 {
+            return None;
+        }
+
+        let weeks = (days as f64 / 7.0).max(1.0);
+        Some(self.commits as f64 / weeks)
+    }
+
+    pub fn days_since_last(&self, today: NaiveDate) -> i64 {
+        (today - self.last_commit).num_days()
+    }
+
+    /// The most days between two consecutive commit days, or 0 with a single commit day
+    pub fn longest_gap(&self) -> i64 {
+        self.commit_days
+            .keys()
+            .zip(self.commit_days.keys().skip(1))
+            .map(|(earlier, later)| (*later - *earlier).num_days())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Entropy of the commits over active days, normalized to 0 (one day) to 1 (an even spread)
+    pub fn spread(&self) -> f64 {
+        let days = self.commit_days.len();
+        if days < 2 {
+            return 0.0;
+        }
+
+        let total: u32 = self.commit_days.values().sum();
+        let entropy: f64 = self
+            .commit_days
+            .values()
+            .map(|&count| {
+                let p = count as f64 / total as f64;
+                -p * p.ln()
+            })
+            .sum();
+        entropy / (days as f64).ln()
+    }
+
+    /// Commits recorded up to and including `first_half_end`, and from `second_half_start` on;
+    /// days in between are not counted
+    pub fn commits_in_halves(
+        &self,
+        first_half_end: NaiveDate,
+        second_half_start: NaiveDate,
+    ) -> (u32, u32) {
+        self.commit_days
+            .iter()
+            .fold((0, 0), |(before, after), (day, count)| {
+                if *day <= first_half_end {
+                    (before + count, after)
+                } else if *day >= second_half_start {
+                    (before, after + count)
+                } else {
+                    (before, after)
+                }
+            })
+    }
+
+    /// Commit counts for the `months` calendar months ending with the month of `end`, oldest first
+    pub fn monthly_series(&self, end: NaiveDate, months: usize) -> Vec<u32> {
+        let end_index = end.year() * 12 + end.month0() as i32;
+
+        (0..months as i32)
+            .rev()
+            .map(|offset| {
+                let index = end_index - offset;
+                let month = (index.rem_euclid(12) + 1) as u32;
+                let year = index.div_euclid(12);
+                self.commits_by_month
+                    .get(&(year, month))
+                    .copied()
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -66,8 +194,165 @@ mod tests {
             commits: 2,
             first_commit: date1,
             last_commit: date2,
+            files_touched: 0,
+            files_changed: 0,
+            aliases: 0,
+            weighted_lines: 0,
+            tags: 0,
+            message_length_total: 0,
+            message_length_max: 0,
+            commits_by_month: BTreeMap::new(),
+            commit_days: BTreeMap::new(),
         };
 
         assert_eq!(user_commit_info.days_between(), (date2 - date1).num_days());
     }
+
+    #[test]
+    fn test_commits_per_week() {
+        let first = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let mut user_commit_info = UserCommitInfo::new(first);
+        user_commit_info.update(first);
+        assert_eq!(user_commit_info.commits_per_week(), None);
+
+        user_commit_info.update(NaiveDate::from_ymd_opt(2023, 1, 4).unwrap());
+        assert_eq!(user_commit_info.commits_per_week(), Some(3.0));
+
+        for _ in 0..3 {
+            user_commit_info.update(NaiveDate::from_ymd_opt(2023, 1, 15).unwrap());
+        }
+        assert_eq!(user_commit_info.commits_per_week(), Some(3.0));
+    }
+
+    #[test]
+    fn test_days_since_last() {
+        let date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let today = NaiveDate::from_ymd_opt(2023, 1, 15).unwrap();
+
+        let user_commit_info = UserCommitInfo::new(date);
+
+        assert_eq!(user_commit_info.days_since_last(today), 14);
+        assert_eq!(user_commit_info.days_since_last(date), 0);
+    }
+
+    #[test]
+    fn test_monthly_series() {
+        let first = NaiveDate::from_ymd_opt(2022, 11, 5).unwrap();
+        let mut user_commit_info = UserCommitInfo::new(first);
+        user_commit_info.record_month(first);
+        for date in [
+            NaiveDate::from_ymd_opt(2022, 11, 20).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 2, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
+        ] {
+            user_commit_info.update(date);
+            user_commit_info.record_month(date);
+        }
+
+        let end = NaiveDate::from_ymd_opt(2023, 3, 15).unwrap();
+        assert_eq!(user_commit_info.monthly_series(end, 5), [2, 0, 0, 1, 0]);
+    }
+
+    #[test]
+    fn test_longest_gap() {
+        let first = NaiveDate::from_ymd_opt(2023, 1, 10).unwrap();
+        let mut user_commit_info = UserCommitInfo::new(first);
+        user_commit_info.record_day(first);
+        assert_eq!(user_commit_info.longest_gap(), 0);
+
+        user_commit_info.update(first);
+        user_commit_info.record_day(first);
+        assert_eq!(user_commit_info.longest_gap(), 0);
+
+        for date in [
+            NaiveDate::from_ymd_opt(2023, 3, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 3, 5).unwrap(),
+        ] {
+            user_commit_info.update(date);
+            user_commit_info.record_day(date);
+        }
+        assert_eq!(user_commit_info.longest_gap(), 50);
+    }
+
+    #[test]
+    fn test_commits_in_halves() {
+        let first = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let mut user_commit_info = UserCommitInfo::new(first);
+        user_commit_info.record_day(first);
+        for day in [10, 20, 20, 31] {
+            let date = NaiveDate::from_ymd_opt(2023, 1, day).unwrap();
+            user_commit_info.update(date);
+            user_commit_info.record_day(date);
+        }
+
+        let first_half_end = NaiveDate::from_ymd_opt(2023, 1, 10).unwrap();
+        assert_eq!(
+            user_commit_info.commits_in_halves(first_half_end, first_half_end.succ_opt().unwrap()),
+            (2, 3)
+        );
+        let second_half_start = NaiveDate::from_ymd_opt(2023, 1, 21).unwrap();
+        assert_eq!(
+            user_commit_info.commits_in_halves(first_half_end, second_half_start),
+            (2, 1)
+        );
+    }
+
+    #[test]
+    fn test_spread() {
+        let record = |days: &[u32]| {
+            let dates: Vec<NaiveDate> = days
+                .iter()
+                .map(|&day| NaiveDate::from_ymd_opt(2023, 1, day).unwrap())
+                .collect();
+            let mut user_commit_info = UserCommitInfo::new(dates[0]);
+            user_commit_info.record_day(dates[0]);
+            for &date in &dates[1..] {
+                user_commit_info.update(date);
+                user_commit_info.record_day(date);
+            }
+            user_commit_info
+        };
+
+        let uniform = record(&[1, 2, 3, 4, 5, 6]);
+        assert!((uniform.spread() - 1.0).abs() < 1e-9);
+
+        let burst = record(&[9, 9, 9, 9, 9, 9, 9, 9, 1, 20]);
+        assert!(burst.spread() < 0.6);
+        assert!(burst.spread() < uniform.spread());
+
+        assert_eq!(record(&[9, 9, 9]).spread(), 0.0);
+    }
+
+    #[test]
+    fn test_equality() {
+        let date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let mut a = UserCommitInfo::new(date);
+        a.update(NaiveDate::from_ymd_opt(2023, 2, 1).unwrap());
+
+        let mut b = a.clone();
+        assert_eq!(a, b);
+
+        b.update(date);
+        assert_ne!(a, b);
+
+        let mut c = UserCommitInfo::new(date);
+        c.update(NaiveDate::from_ymd_opt(2023, 2, 1).unwrap());
+        c.files_touched = 3;
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_message_lengths() {
+        let date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let mut user_commit_info = UserCommitInfo::new(date);
+        user_commit_info.record_message_length(10);
+        for length in [0, 25, 5] {
+            user_commit_info.update(date);
+            user_commit_info.record_message_length(length);
+        }
+
+        assert_eq!(user_commit_info.average_message_length(), 10.0);
+        assert_eq!(user_commit_info.message_length_max, 25);
+    }
 }
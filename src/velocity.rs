@@ -0,0 +1,128 @@
+use chrono::{Datelike, NaiveDate};
+
+use crate::user_commit_info::UserCommitInfo;
+
+/// Number of months before the most recent one that make up the trailing average
+const TRAILING_MONTHS: usize = 6;
+
+/// How the most recent month's commit count compares with the trailing average
+#[derive(Debug, PartialEq)]
+pub enum VelocityAlert {
+    Low { recent: u32, average: f64 },
+    High { recent: u32, average: f64 },
+}
+
+impl VelocityAlert {
+    pub fn message(&self) -> String {
+        match self {
+            VelocityAlert::Low { recent, average } => format!(
+                "{} commits last month, below the {}-month average of {:.1}",
+                recent, TRAILING_MONTHS, average
+            ),
+            VelocityAlert::High { recent, average } => format!(
+                "{} commits last month, above the {}-month average of {:.1}",
+                recent, TRAILING_MONTHS, average
+            ),
+        }
+    }
+}
+
+/// Compare the last value of `series` (oldest first) with the average of the months before it,
+/// alerting when it falls below `low` or rises above `high` times that average
+fn velocity_alert(series: &[u32], low: Option<f64>, high: Option<f64>) -> Option<VelocityAlert> {
+    let (&recent, trailing) = series.split_last()?;
+    if trailing.is_empty() {
+        return None;
+    }
+
+    let average = trailing.iter().sum::<u32>() as f64 / trailing.len() as f64;
+    if average == 0.0 {
+        return None;
+    }
+
+    if low.is_some_and(|factor| (recent as f64) < average * factor) {
+        Some(VelocityAlert::Low { recent, average })
+    } else if high.is_some_and(|factor| recent as f64 > average * factor) {
+        Some(VelocityAlert::High { recent, average })
+    } else {
+        None
+    }
+}
+
+/// Check the repository-wide commit count of the last complete month before `today`
+pub fn check_velocity(
+    authors: &[(String, UserCommitInfo)],
+    today: NaiveDate,
+    low: Option<f64>,
+    high: Option<f64>,
+) -> Option<VelocityAlert> {
+    let last_month = today.with_day(1)?.pred_opt()?;
+
+    let mut series = vec![0; TRAILING_MONTHS + 1];
+    for (_, user_commit_info) in authors {
+        let monthly = user_commit_info.monthly_series(last_month, TRAILING_MONTHS + 1);
+        for (total, count) in series.iter_mut().zip(monthly) {
+            *total += count;
+        }
+    }
+
+    velocity_alert(&series, low, high)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_velocity_alert() {
+        let series = [10, 12, 8, 10, 10, 10, 4];
+        assert_eq!(
+            velocity_alert(&series, Some(0.5), None),
+            Some(VelocityAlert::Low {
+                recent: 4,
+                average: 10.0
+            })
+        );
+        assert_eq!(velocity_alert(&series, Some(0.3), Some(2.0)), None);
+
+        let series = [10, 12, 8, 10, 10, 10, 25];
+        assert_eq!(
+            velocity_alert(&series, Some(0.5), Some(2.0)),
+            Some(VelocityAlert::High {
+                recent: 25,
+                average: 10.0
+            })
+        );
+        assert_eq!(velocity_alert(&series, Some(0.5), None), None);
+    }
+
+    #[test]
+    fn test_velocity_alert_needs_history() {
+        assert_eq!(velocity_alert(&[5], Some(2.0), None), None);
+        assert_eq!(velocity_alert(&[0, 0, 3], None, Some(1.0)), None);
+    }
+
+    #[test]
+    fn test_check_velocity_uses_last_complete_month() {
+        let first = NaiveDate::from_ymd_opt(2023, 1, 10).unwrap();
+        let mut user_commit_info = UserCommitInfo::new(first);
+        user_commit_info.record_month(first);
+        let later = (1..=5)
+            .map(|day| NaiveDate::from_ymd_opt(2023, 2, day).unwrap())
+            .chain([NaiveDate::from_ymd_opt(2023, 6, 20).unwrap()]);
+        for date in later {
+            user_commit_info.update(date);
+            user_commit_info.record_month(date);
+        }
+        let authors = vec![("alice@example.com".to_string(), user_commit_info)];
+
+        let today = NaiveDate::from_ymd_opt(2023, 7, 3).unwrap();
+        assert_eq!(
+            check_velocity(&authors, today, None, Some(0.5)),
+            Some(VelocityAlert::High {
+                recent: 1,
+                average: 1.0
+            })
+        );
+    }
+}